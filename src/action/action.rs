@@ -272,6 +272,10 @@ impl Action {
         HANDLER_TYPES.iter()
     }
 
+    pub(crate) fn from_handler_str(name: &str) -> Option<Self> {
+        Self::builtin_handlers().find(|action| action.as_handler_str() == name).cloned()
+    }
+
     pub fn as_handler_str(&self) -> &'static str {
         match *self {
             FIND_UNIQUE_HANDLER => "findUnique",
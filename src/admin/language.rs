@@ -1,7 +1,7 @@
 use serde::Serialize;
 use teo_result::{Result, Error};
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub enum Language {
     EnUs,
     EnUk,
@@ -66,4 +66,38 @@ impl Language {
             Language::ZhTw => "中文（繁體）",
         }
     }
-}
\ No newline at end of file
+
+    /// The supported language which `Accept-Language` prefers most.
+    pub fn from_accept_language(accept_language: &str) -> Option<Self> {
+        let mut result: Option<(Self, f32)> = None;
+        for item in accept_language.split(',') {
+            let mut parts = item.split(';');
+            let tag = parts.next().unwrap_or("").trim().to_lowercase();
+            let quality = parts.find_map(|p| p.trim().strip_prefix("q=").and_then(|q| q.parse::<f32>().ok())).unwrap_or(1.0);
+            if let Some(language) = Self::from_language_tag(&tag) {
+                if quality > 0.0 && result.as_ref().map_or(true, |(_, q)| quality > *q) {
+                    result = Some((language, quality));
+                }
+            }
+        }
+        result.map(|(l, _)| l)
+    }
+
+    fn from_language_tag(tag: &str) -> Option<Self> {
+        let mut subtags = tag.split(|c| c == '-' || c == '_');
+        let primary = subtags.next()?;
+        let rest: Vec<&str> = subtags.collect();
+        Some(match primary {
+            "en" => if rest.iter().any(|s| *s == "gb" || *s == "uk") { Self::EnUk } else { Self::EnUs },
+            "de" => Self::De,
+            "fr" => Self::Fr,
+            "es" => Self::Es,
+            "hi" => Self::Hi,
+            "he" | "iw" => Self::He,
+            "ja" => Self::Ja,
+            "ko" => Self::Ko,
+            "zh" => if rest.iter().any(|s| *s == "tw" || *s == "hk" || *s == "mo" || *s == "hant") { Self::ZhTw } else { Self::ZhCn },
+            _ => None?,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use std::str::FromStr;
+use bigdecimal::BigDecimal;
+use bson::oid::ObjectId;
+use chrono::{DateTime, NaiveDate, Utc};
+use key_path::{path, KeyPath};
+use serde_json::Value as JsonValue;
+use teo_parser::r#type::Type;
+use teo_result::{Error, Result};
+use crate::coder::json_to_teon::json_to_teon_with_type;
+use crate::coder::type_tag::{strip_type_tags, type_tag};
+use crate::namespace::Namespace;
+use crate::value::file::File;
+use crate::value::Value;
+
+/// A binary wire encoding of teon values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BinaryFormat {
+    MessagePack,
+    Cbor,
+}
+
+impl BinaryFormat {
+
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type.split(';').next().unwrap_or("").trim().to_lowercase().as_str() {
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => Some(BinaryFormat::MessagePack),
+            "application/cbor" => Some(BinaryFormat::Cbor),
+            _ => None,
+        }
+    }
+
+    /// The binary format requested by an `Accept` header. `None` is returned
+    /// when json is acceptable with the same or a higher quality.
+    pub fn from_accept(accept: &str) -> Option<Self> {
+        let mut result: Option<(Self, f32)> = None;
+        let mut json_quality: f32 = 0.0;
+        for item in accept.split(',') {
+            let mut parts = item.split(';');
+            let media_type = parts.next().unwrap_or("").trim();
+            let quality = parts.find_map(|p| p.trim().strip_prefix("q=").and_then(|q| q.parse::<f32>().ok())).unwrap_or(1.0);
+            if let Some(format) = Self::from_content_type(media_type) {
+                if quality > 0.0 && result.map_or(true, |(_, q)| quality > q) {
+                    result = Some((format, quality));
+                }
+            } else if media_type == "application/json" || media_type == "*/*" || media_type == "application/*" {
+                json_quality = json_quality.max(quality);
+            }
+        }
+        result.and_then(|(format, quality)| if quality > json_quality { Some(format) } else { None })
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            BinaryFormat::MessagePack => "application/msgpack",
+            BinaryFormat::Cbor => "application/cbor",
+        }
+    }
+}
+
+/// Encode a value. Types which json can't tell apart are written as single
+/// entry maps like `{"$int64": 1}`, the same markers `Value` serializes with.
+pub fn teon_to_binary(value: &Value, format: BinaryFormat) -> Result<Vec<u8>> {
+    match format {
+        BinaryFormat::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| Error::internal_server_error_message(e.to_string())),
+        BinaryFormat::Cbor => {
+            let mut bytes = vec![];
+            ciborium::into_writer(value, &mut bytes).map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            Ok(bytes)
+        }
+    }
+}
+
+/// Decode a value written by `teon_to_binary`, restoring the tagged types.
+pub fn binary_to_teon(bytes: &[u8], format: BinaryFormat) -> Result<Value> {
+    tagged_json_to_teon(&decode_binary(bytes, format)?, &path![])
+}
+
+/// Decode a binary body and validate it against the type `t`. Type tags are
+/// accepted in place of the plain values json clients send.
+pub fn binary_to_teon_with_type(bytes: &[u8], format: BinaryFormat, path: &KeyPath, t: &Type, main_namespace: &Namespace) -> Result<Value> {
+    json_to_teon_with_type(&strip_type_tags(decode_binary(bytes, format)?), path, t, main_namespace)
+}
+
+fn decode_binary(bytes: &[u8], format: BinaryFormat) -> Result<JsonValue> {
+    match format {
+        BinaryFormat::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| Error::invalid_request_message(format!("invalid msgpack body: {}", e))),
+        BinaryFormat::Cbor => ciborium::from_reader(bytes).map_err(|e| Error::invalid_request_message(format!("invalid cbor body: {}", e))),
+    }
+}
+
+fn tagged_json_to_teon(json: &JsonValue, path: &KeyPath) -> Result<Value> {
+    if let Some((tag, value)) = type_tag(json) {
+        let invalid = || Error::invalid_request_pathed(path.clone(), format!("invalid {}", tag));
+        return Ok(match tag {
+            "$int" => Value::Int(value.as_i64().and_then(|i| i32::try_from(i).ok()).ok_or_else(invalid)?),
+            "$int64" => Value::Int64(value.as_i64().ok_or_else(invalid)?),
+            "$float32" => Value::Float32(value.as_f64().ok_or_else(invalid)? as f32),
+            "$float" => Value::Float(value.as_f64().ok_or_else(invalid)?),
+            "$decimal" => Value::Decimal(value.as_str().and_then(|s| BigDecimal::from_str(s).ok()).ok_or_else(invalid)?),
+            "$objectId" => Value::ObjectId(value.as_str().and_then(|s| ObjectId::parse_str(s).ok()).ok_or_else(invalid)?),
+            "$date" => Value::Date(value.as_str().and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()).ok_or_else(invalid)?),
+            "$datetime" => Value::DateTime(value.as_str().and_then(|s| DateTime::parse_from_rfc3339(s).ok()).ok_or_else(invalid)?.with_timezone(&Utc)),
+            "$file" => Value::File(File::try_from(value).map_err(|_| invalid())?),
+            _ => unreachable!(),
+        });
+    }
+    Ok(match json {
+        JsonValue::Array(array) => Value::Array(array.iter().enumerate().map(|(i, j)| tagged_json_to_teon(j, &(path + i))).collect::<Result<Vec<Value>>>()?),
+        JsonValue::Object(map) => Value::Dictionary(map.iter().map(|(k, j)| Ok((k.clone(), tagged_json_to_teon(j, &(path + k))?))).collect::<Result<_>>()?),
+        json => Value::from(json),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use super::*;
+
+    fn roundtrip(value: Value) {
+        for format in [BinaryFormat::MessagePack, BinaryFormat::Cbor] {
+            let bytes = teon_to_binary(&value, format).unwrap();
+            assert_eq!(binary_to_teon(&bytes, format).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn roundtrips_tagged_types() {
+        roundtrip(Value::Int(5));
+        roundtrip(Value::Int64(1 << 40));
+        roundtrip(Value::Float32(1.5));
+        roundtrip(Value::Float(2.25));
+        roundtrip(Value::Decimal(BigDecimal::from_str("12.34").unwrap()));
+        roundtrip(Value::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()));
+        roundtrip(Value::Array(vec![Value::String("a".to_owned()), Value::Bool(true), Value::Null]));
+    }
+
+    #[test]
+    fn keeps_user_dictionaries_with_dollar_keys() {
+        roundtrip(Value::Dictionary(indexmap! { "$name".to_owned() => Value::String("x".to_owned()) }));
+        roundtrip(Value::Dictionary(indexmap! { "$where".to_owned() => Value::Dictionary(indexmap! { "id".to_owned() => Value::Int(1) }) }));
+    }
+
+    #[test]
+    fn prefers_json_on_equal_quality() {
+        assert_eq!(BinaryFormat::from_accept("application/msgpack"), Some(BinaryFormat::MessagePack));
+        assert_eq!(BinaryFormat::from_accept("application/json, application/cbor;q=0.5"), None);
+        assert_eq!(BinaryFormat::from_accept("application/cbor, */*"), None);
+        assert_eq!(BinaryFormat::from_accept("application/cbor, */*;q=0.1"), Some(BinaryFormat::Cbor));
+    }
+}
@@ -0,0 +1,181 @@
+use std::str::FromStr;
+use indexmap::IndexMap;
+use key_path::{path, KeyPath};
+use serde_json::{Number as JsonNumber, Value as JsonValue};
+use teo_parser::r#type::synthesized_shape::SynthesizedShape;
+use teo_parser::r#type::Type;
+use crate::coder::json_to_teon::{fetch_input, json_to_teon_with_shape, json_to_teon_with_type};
+use crate::namespace::Namespace;
+use crate::value::Value;
+
+/// Insert a form or query string entry into `form`.
+///
+/// Keys support the bracket notation: `a[b]=1` creates a nested dictionary
+/// and `a[]=1` appends to an array. A key which is repeated collects its
+/// values into an array. Keys with more than `MAX_FORM_KEY_SEGMENTS`
+/// segments are rejected.
+pub fn insert_form_value(form: &mut IndexMap<String, Value>, key: &str, value: Value) -> teo_result::Result<()> {
+    let segments = form_key_segments(key);
+    if segments.len() > MAX_FORM_KEY_SEGMENTS {
+        return Err(teo_result::Error::invalid_request_pathed(path![key], "key is nested too deeply"));
+    }
+    let (first, rest) = segments.split_first().unwrap();
+    if rest.is_empty() {
+        insert_or_append(form, first, value);
+    } else {
+        let child = form.entry(first.to_string()).or_insert(Value::Null);
+        insert_segments(child, rest, value);
+    }
+    Ok(())
+}
+
+/// The maximal number of segments in a form key, `a[b][c]` has three.
+pub const MAX_FORM_KEY_SEGMENTS: usize = 32;
+
+fn form_key_segments(key: &str) -> Vec<&str> {
+    match key.find('[') {
+        Some(index) if index > 0 && key.ends_with(']') => {
+            let mut segments = vec![&key[..index]];
+            segments.extend(key[index + 1..key.len() - 1].split("]["));
+            segments
+        }
+        _ => vec![key],
+    }
+}
+
+fn insert_segments(target: &mut Value, segments: &[&str], value: Value) {
+    let (first, rest) = segments.split_first().unwrap();
+    if first.is_empty() {
+        if !target.is_array() {
+            *target = Value::Array(vec![]);
+        }
+        let array = target.as_array_mut().unwrap();
+        if rest.is_empty() {
+            array.push(value);
+        } else {
+            let mut child = Value::Null;
+            insert_segments(&mut child, rest, value);
+            array.push(child);
+        }
+        return;
+    }
+    if !target.is_dictionary() {
+        *target = Value::Dictionary(IndexMap::new());
+    }
+    let map = target.as_dictionary_mut().unwrap();
+    if rest.is_empty() {
+        insert_or_append(map, first, value);
+    } else {
+        let child = map.entry(first.to_string()).or_insert(Value::Null);
+        insert_segments(child, rest, value);
+    }
+}
+
+fn insert_or_append(map: &mut IndexMap<String, Value>, key: &str, value: Value) {
+    match map.get_mut(key) {
+        Some(Value::Array(array)) => array.push(value),
+        Some(existing) => {
+            let previous = std::mem::replace(existing, Value::Null);
+            *existing = Value::Array(vec![previous, value]);
+        }
+        None => {
+            map.insert(key.to_owned(), value);
+        }
+    }
+}
+
+/// Convert a decoded form or query string into a value of type `t`.
+///
+/// Form values arrive as strings, so they are converted into the numbers and
+/// bools the type expects before the usual json validation runs. Untyped
+/// inputs are returned as decoded.
+pub fn form_to_teon_with_type(form: Value, path: &KeyPath, t: &Type, main_namespace: &Namespace) -> teo_result::Result<Value> {
+    match t {
+        Type::Undetermined | Type::Ignored | Type::Any => Ok(form),
+        _ => {
+            let json: JsonValue = form.try_into()?;
+            let json = coerce_form_json_with_type(json, t, main_namespace);
+            json_to_teon_with_type(&json, path, t, main_namespace)
+        }
+    }
+}
+
+pub fn form_to_teon_with_shape(form: Value, path: &KeyPath, shape: &SynthesizedShape, main_namespace: &Namespace) -> teo_result::Result<Value> {
+    let json: JsonValue = form.try_into()?;
+    let json = coerce_form_json_with_shape(json, shape, main_namespace);
+    json_to_teon_with_shape(&json, path, shape, main_namespace)
+}
+
+fn coerce_form_json_with_type(json: JsonValue, t: &Type, main_namespace: &Namespace) -> JsonValue {
+    match t {
+        Type::Null => match json.as_str() {
+            Some("") | Some("null") => JsonValue::Null,
+            _ => json,
+        },
+        Type::Bool => match json.as_str() {
+            Some("true") | Some("1") | Some("on") => JsonValue::Bool(true),
+            Some("false") | Some("0") | Some("off") => JsonValue::Bool(false),
+            _ => json,
+        },
+        Type::Int | Type::Int64 => match json.as_str().and_then(|s| i64::from_str(s).ok()) {
+            Some(i) => JsonValue::Number(JsonNumber::from(i)),
+            None => json,
+        },
+        Type::Float32 | Type::Float => match json.as_str().and_then(|s| f64::from_str(s).ok()).and_then(JsonNumber::from_f64) {
+            Some(n) => JsonValue::Number(n),
+            None => json,
+        },
+        Type::Optional(inner) => coerce_form_json_with_type(json, inner.as_ref(), main_namespace),
+        Type::Enumerable(inner) => match json {
+            JsonValue::Array(array) => JsonValue::Array(array.into_iter().map(|j| coerce_form_json_with_type(j, inner.as_ref(), main_namespace)).collect()),
+            json => coerce_form_json_with_type(json, inner.as_ref(), main_namespace),
+        },
+        Type::Array(inner) => match decode_embedded_json(json) {
+            JsonValue::Array(array) => JsonValue::Array(array.into_iter().map(|j| coerce_form_json_with_type(j, inner.as_ref(), main_namespace)).collect()),
+            json => JsonValue::Array(vec![coerce_form_json_with_type(json, inner.as_ref(), main_namespace)]),
+        },
+        Type::Dictionary(inner) => match decode_embedded_json(json) {
+            JsonValue::Object(map) => JsonValue::Object(map.into_iter().map(|(k, j)| (k, coerce_form_json_with_type(j, inner.as_ref(), main_namespace))).collect()),
+            json => json,
+        },
+        Type::InterfaceObject(reference, gens) => {
+            let interface = main_namespace.interface_at_path(&reference.str_path()).unwrap();
+            coerce_form_json_with_shape(json, &interface.shape_from_generics(gens), main_namespace)
+        }
+        Type::SynthesizedShapeReference(shape_reference) => {
+            coerce_form_json_with_type(json, fetch_input(shape_reference, main_namespace), main_namespace)
+        }
+        Type::SynthesizedShape(shape) => coerce_form_json_with_shape(json, shape, main_namespace),
+        Type::DeclaredSynthesizedShape(shape_reference, model_type) => {
+            let shape = model_type.as_model_object()
+                .and_then(|m| main_namespace.model_at_path(&m.str_path()))
+                .and_then(|m| m.cache.shape.get_declared(shape_reference.string_path()));
+            match shape {
+                Some(shape) => coerce_form_json_with_shape(json, shape, main_namespace),
+                None => json,
+            }
+        }
+        _ => json,
+    }
+}
+
+fn coerce_form_json_with_shape(json: JsonValue, shape: &SynthesizedShape, main_namespace: &Namespace) -> JsonValue {
+    match decode_embedded_json(json) {
+        JsonValue::Object(map) => JsonValue::Object(map.into_iter().map(|(k, j)| {
+            let j = match shape.get(&k) {
+                Some(t) => coerce_form_json_with_type(j, t, main_namespace),
+                None => j,
+            };
+            (k, j)
+        }).collect()),
+        json => json,
+    }
+}
+
+/// Structured values may also be sent as a single json encoded field.
+fn decode_embedded_json(json: JsonValue) -> JsonValue {
+    match json.as_str() {
+        Some(s) if s.starts_with('{') || s.starts_with('[') => serde_json::from_str(s).unwrap_or(json),
+        _ => json,
+    }
+}
@@ -1,3 +1,8 @@
 pub mod json_to_teon;
+pub mod form_to_teon;
+pub mod binary;
+pub mod type_tag;
 
-pub use json_to_teon::json_to_teon;
\ No newline at end of file
+pub use json_to_teon::json_to_teon;
+pub use form_to_teon::form_to_teon_with_type;
+pub use binary::{BinaryFormat, binary_to_teon, teon_to_binary};
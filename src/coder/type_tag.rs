@@ -0,0 +1,46 @@
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// The markers `Value` serializes with and which can be decoded back.
+const TYPE_TAGS: [&str; 9] = ["$int", "$int64", "$float32", "$float", "$decimal", "$objectId", "$date", "$datetime", "$file"];
+
+/// A single entry map is a type tag only when its key is one of the known
+/// markers, so user dictionaries like `{"$name": 1}` are kept as they are.
+pub(crate) fn type_tag(json: &JsonValue) -> Option<(&str, &JsonValue)> {
+    let map = json.as_object()?;
+    if map.len() != 1 {
+        return None;
+    }
+    let (key, value) = map.iter().next().unwrap();
+    TYPE_TAGS.contains(&key.as_str()).then_some((key.as_str(), value))
+}
+
+/// Replace type tags with the plain values handler input validation expects.
+pub(crate) fn strip_type_tags(json: JsonValue) -> JsonValue {
+    if let Some((_, value)) = type_tag(&json) {
+        return value.clone();
+    }
+    match json {
+        JsonValue::Array(array) => JsonValue::Array(array.into_iter().map(strip_type_tags).collect()),
+        JsonValue::Object(map) => JsonValue::Object(map.into_iter().map(|(k, j)| (k, strip_type_tags(j))).collect::<JsonMap<String, JsonValue>>()),
+        json => json,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn recognizes_known_tags_only() {
+        assert_eq!(type_tag(&json!({ "$int64": 1 })), Some(("$int64", &json!(1))));
+        assert_eq!(type_tag(&json!({ "$name": 1 })), None);
+        assert_eq!(type_tag(&json!({ "$int": 1, "b": 2 })), None);
+    }
+
+    #[test]
+    fn strips_only_known_tags() {
+        let json = json!({ "a": { "$int64": 1 }, "b": { "$name": 2 }, "c": [{ "$date": "2024-01-01" }] });
+        assert_eq!(strip_type_tags(json), json!({ "a": 1, "b": { "$name": 2 }, "c": ["2024-01-01"] }));
+    }
+}
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -5,4 +6,58 @@ pub struct Server {
     pub bind: (String, i32),
     #[serde(rename = "pathPrefix")]
     pub path_prefix: Option<String>,
+    pub compression: Option<Compression>,
+    /// Whether teon responses are encoded as MessagePack or CBOR when the
+    /// `Accept` header prefers them.
+    pub binary: bool,
+    /// Requests running longer than this are cancelled with 504.
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Compression {
+    /// Bodies smaller than this amount of bytes are sent as they are.
+    pub threshold: usize,
+    /// Encodings the server may respond with.
+    pub encodings: Vec<Encoding>,
+}
+
+impl Default for Compression {
+
+    fn default() -> Self {
+        Self {
+            threshold: 1024,
+            encodings: vec![Encoding::Brotli, Encoding::Gzip, Encoding::Deflate],
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq)]
+pub enum Encoding {
+    #[serde(rename = "br")]
+    Brotli,
+    #[serde(rename = "gzip")]
+    Gzip,
+    #[serde(rename = "deflate")]
+    Deflate,
+}
+
+impl Encoding {
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "br" | "brotli" => Some(Encoding::Brotli),
+            "gzip" => Some(Encoding::Gzip),
+            "deflate" => Some(Encoding::Deflate),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
 }
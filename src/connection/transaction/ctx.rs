@@ -1,8 +1,10 @@
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
 use std::future::Future;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
+use educe::Educe;
+use futures_util::future::BoxFuture;
 use key_path::KeyPath;
 use maplit::btreemap;
 use teo_result::{Result, Error};
@@ -20,11 +22,18 @@ pub struct Ctx {
     inner: Arc<CtxInner>
 }
 
-#[derive(Debug)]
+type Hook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+#[derive(Educe)]
+#[educe(Debug)]
 struct CtxInner {
     connection_ctx: connection::Ctx,
     is_transaction: AtomicBool,
-    transactions: tokio::sync::Mutex<BTreeMap<Vec<String>, Arc<dyn Transaction>>>
+    transactions: tokio::sync::Mutex<BTreeMap<Vec<String>, Arc<dyn Transaction>>>,
+    #[educe(Debug(ignore))]
+    after_commit_hooks: Mutex<Vec<Hook>>,
+    #[educe(Debug(ignore))]
+    after_abort_hooks: Mutex<Vec<Hook>>,
 }
 
 impl Ctx {
@@ -34,7 +43,9 @@ impl Ctx {
             inner: Arc::new(CtxInner {
                 connection_ctx,
                 is_transaction: AtomicBool::new(false),
-                transactions: tokio::sync::Mutex::new(btreemap!{})
+                transactions: tokio::sync::Mutex::new(btreemap!{}),
+                after_commit_hooks: Mutex::new(vec![]),
+                after_abort_hooks: Mutex::new(vec![]),
             })
         }
     }
@@ -44,7 +55,9 @@ impl Ctx {
             inner: Arc::new(CtxInner {
                 connection_ctx: self.inner.connection_ctx.clone(),
                 is_transaction: AtomicBool::new(true),
-                transactions: tokio::sync::Mutex::new(btreemap!{})
+                transactions: tokio::sync::Mutex::new(btreemap!{}),
+                after_commit_hooks: Mutex::new(vec![]),
+                after_abort_hooks: Mutex::new(vec![]),
             })
         }
     }
@@ -54,7 +67,9 @@ impl Ctx {
             inner: Arc::new(CtxInner {
                 connection_ctx: self.inner.connection_ctx.clone(),
                 is_transaction: AtomicBool::new(false),
-                transactions: tokio::sync::Mutex::new(btreemap!{})
+                transactions: tokio::sync::Mutex::new(btreemap!{}),
+                after_commit_hooks: Mutex::new(vec![]),
+                after_abort_hooks: Mutex::new(vec![]),
             })
         }
     }
@@ -170,7 +185,9 @@ impl Ctx {
         Fut: Future<Output = teo_result::Result<R>> {
         let ctx = self.transaction_copy();
         let ctx_clone = ctx.clone();
+        let mut guard = AbortOnDrop(Some(ctx.clone()));
         let result = f((&ctx_clone).into()).await;
+        guard.0 = None;
         if result.is_ok() {
             ctx.commit().await?;
         } else {
@@ -179,25 +196,66 @@ impl Ctx {
         Ok(result?)
     }
 
+    /// Run `hook` once the changes made through this ctx are committed. Outside a
+    /// transaction the changes are already persisted and `hook` runs at once.
+    pub async fn after_commit<F, Fut>(&self, hook: F) where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static {
+        if self.inner.is_transaction.load(Ordering::SeqCst) {
+            self.inner.after_commit_hooks.lock().unwrap().push(Box::new(move || Box::pin(hook())));
+        } else {
+            hook().await;
+        }
+    }
+
+    /// Run `hook` when the changes made through this ctx are rolled back. Outside a
+    /// transaction nothing is rolled back and `hook` is dropped.
+    pub fn after_abort<F, Fut>(&self, hook: F) where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static {
+        if self.inner.is_transaction.load(Ordering::SeqCst) {
+            self.inner.after_abort_hooks.lock().unwrap().push(Box::new(move || Box::pin(hook())));
+        }
+    }
+
+    async fn run_hooks(&self, committed: bool) {
+        let after_commit_hooks = std::mem::take(&mut *self.inner.after_commit_hooks.lock().unwrap());
+        let after_abort_hooks = std::mem::take(&mut *self.inner.after_abort_hooks.lock().unwrap());
+        for hook in if committed { after_commit_hooks } else { after_abort_hooks } {
+            hook().await;
+        }
+    }
+
     async fn abort(&self) -> Result<()> {
+        let mut result = Ok(());
         for transaction in self.inner.transactions.lock().await.values() {
             if transaction.is_transaction() {
-                transaction.abort().await?;
+                if let Err(error) = transaction.abort().await {
+                    result = Err(error);
+                }
             }
         }
         *self.inner.transactions.lock().await = btreemap! {};
         self.inner.is_transaction.store(false, Ordering::SeqCst);
-        Ok(())
+        self.run_hooks(false).await;
+        result
     }
 
     async fn commit(&self) -> Result<()> {
-        for transaction in self.inner.transactions.lock().await.values() {
+        let transactions: Vec<Arc<dyn Transaction>> = self.inner.transactions.lock().await.values().cloned().collect();
+        for transaction in transactions {
             if transaction.is_transaction() {
-                transaction.commit().await?;
+                if let Err(error) = transaction.commit().await {
+                    if let Err(abort_error) = self.abort().await {
+                        log::error!("cannot abort transaction after failed commit: {}", abort_error.message());
+                    }
+                    return Err(error);
+                }
             }
         }
         *self.inner.transactions.lock().await = btreemap! {};
         self.inner.is_transaction.store(false, Ordering::SeqCst);
+        self.run_hooks(true).await;
         Ok(())
     }
 
@@ -331,6 +389,29 @@ impl Ctx {
     }
 }
 
+/// Aborts the transaction of a `run_transaction` call which is cancelled
+/// before it finishes, e.g. by a timeout.
+struct AbortOnDrop(Option<Ctx>);
+
+impl Drop for AbortOnDrop {
+
+    fn drop(&mut self) {
+        let Some(ctx) = self.0.take() else {
+            return;
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(error) = ctx.abort().await {
+                        log::error!("cannot abort cancelled transaction: {}", error.message());
+                    }
+                });
+            }
+            Err(_) => log::error!("cannot abort cancelled transaction: no runtime"),
+        }
+    }
+}
+
 impl From<&Ctx> for Ctx {
 
     fn from(value: &Ctx) -> Self {
@@ -342,4 +423,96 @@ impl ExtractFromTransactionCtx for Ctx {
     fn extract(ctx: &Ctx) -> Self {
         ctx.clone()
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use teo_result::Error;
+    use crate::connection;
+    use crate::namespace::Namespace;
+    use super::Ctx;
+
+    fn ctx() -> Ctx {
+        let namespace: &'static Namespace = Box::leak(Box::new(Namespace::main()));
+        Ctx::new(connection::Ctx::from_namespace(namespace))
+    }
+
+    fn counter_hook(counter: &Arc<AtomicUsize>) -> impl FnOnce() -> futures_util::future::Ready<()> + Send + 'static {
+        let counter = counter.clone();
+        move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            futures_util::future::ready(())
+        }
+    }
+
+    #[tokio::test]
+    async fn after_commit_hooks_run_once_the_transaction_commits() {
+        let committed = Arc::new(AtomicUsize::new(0));
+        let aborted = Arc::new(AtomicUsize::new(0));
+        let (c, a) = (committed.clone(), aborted.clone());
+        ctx().run_transaction(move |ctx: Ctx| {
+            let (c, a) = (c.clone(), a.clone());
+            async move {
+                ctx.after_commit(counter_hook(&c)).await;
+                ctx.after_abort(counter_hook(&a));
+                assert_eq!(c.load(Ordering::SeqCst), 0);
+                Ok(())
+            }
+        }).await.unwrap();
+        assert_eq!(committed.load(Ordering::SeqCst), 1);
+        assert_eq!(aborted.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn after_abort_hooks_run_when_the_transaction_fails() {
+        let committed = Arc::new(AtomicUsize::new(0));
+        let aborted = Arc::new(AtomicUsize::new(0));
+        let (c, a) = (committed.clone(), aborted.clone());
+        let result: teo_result::Result<()> = ctx().run_transaction(move |ctx: Ctx| {
+            let (c, a) = (c.clone(), a.clone());
+            async move {
+                ctx.after_commit(counter_hook(&c)).await;
+                ctx.after_abort(counter_hook(&a));
+                Err(Error::new("failed"))
+            }
+        }).await;
+        assert!(result.is_err());
+        assert_eq!(committed.load(Ordering::SeqCst), 0);
+        assert_eq!(aborted.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn after_commit_hooks_run_at_once_outside_a_transaction() {
+        let committed = Arc::new(AtomicUsize::new(0));
+        let aborted = Arc::new(AtomicUsize::new(0));
+        let ctx = ctx();
+        ctx.after_commit(counter_hook(&committed)).await;
+        ctx.after_abort(counter_hook(&aborted));
+        assert_eq!(committed.load(Ordering::SeqCst), 1);
+        assert_eq!(aborted.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn nested_run_transaction_commits_on_its_own() {
+        let committed = Arc::new(AtomicUsize::new(0));
+        let c = committed.clone();
+        ctx().run_transaction(move |outer: Ctx| {
+            let c = c.clone();
+            async move {
+                let (inner_c, outer_clone) = (c.clone(), outer.clone());
+                outer.run_transaction(move |inner: Ctx| {
+                    let (c, outer) = (inner_c.clone(), outer_clone.clone());
+                    async move {
+                        assert!(!Arc::ptr_eq(&outer.inner, &inner.inner));
+                        inner.after_commit(counter_hook(&c)).await;
+                        Ok(())
+                    }
+                }).await?;
+                assert_eq!(c.load(Ordering::SeqCst), 1);
+                Ok(())
+            }
+        }).await.unwrap();
+        assert_eq!(committed.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,174 @@
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use aes_gcm::aead::{Aead, Payload};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use teo_result::{Error, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const NONCE_LENGTH: usize = 12;
+
+/// Secret keys for signed and encrypted cookies.
+///
+/// The first key signs and encrypts new cookies. Every key is tried when
+/// reading, so a new key can be put in front while cookies issued with the
+/// older keys stay readable until the older keys are removed.
+#[derive(Clone)]
+pub struct CookieKeys {
+    keys: Vec<DerivedKey>,
+}
+
+#[derive(Clone)]
+struct DerivedKey {
+    signing: [u8; 32],
+    encryption: [u8; 32],
+}
+
+impl DerivedKey {
+
+    fn new(secret: &[u8]) -> Self {
+        Self {
+            signing: derive(secret, b"teo-cookie-signing"),
+            encryption: derive(secret, b"teo-cookie-encryption"),
+        }
+    }
+}
+
+fn derive(secret: &[u8], purpose: &[u8]) -> [u8; 32] {
+    let mut mac = HmacSha256::new_from_slice(secret).unwrap();
+    mac.update(purpose);
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&mac.finalize().into_bytes());
+    key
+}
+
+impl CookieKeys {
+
+    /// Create from secrets, the current one first.
+    pub fn new<I, S>(secrets: I) -> Result<Self> where I: IntoIterator<Item = S>, S: AsRef<[u8]> {
+        let mut keys = vec![];
+        for secret in secrets {
+            if secret.as_ref().is_empty() {
+                return Err(Error::new("cookie key should not be empty"));
+            }
+            keys.push(DerivedKey::new(secret.as_ref()));
+        }
+        if keys.is_empty() {
+            return Err(Error::new("at least one cookie key is required"));
+        }
+        Ok(Self { keys })
+    }
+
+    /// Sign `value`. The signature covers the cookie name, so a signed value
+    /// cannot be moved into another cookie.
+    pub fn sign(&self, name: &str, value: &str) -> String {
+        let signature = signature(&self.keys[0], name, value).finalize().into_bytes();
+        format!("{}.{}", value, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    /// Return the original value if `signed` carries a valid signature made
+    /// with any of the keys.
+    pub fn verify(&self, name: &str, signed: &str) -> Option<String> {
+        let (value, signature) = signed.rsplit_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        self.keys.iter().any(|key| {
+            signature_verified(key, name, value, &signature)
+        }).then(|| value.to_owned())
+    }
+
+    pub fn encrypt(&self, name: &str, value: &str) -> String {
+        let cipher = Aes256Gcm::new_from_slice(&self.keys[0].encryption).unwrap();
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), Payload { msg: value.as_bytes(), aad: name.as_bytes() }).unwrap();
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        URL_SAFE_NO_PAD.encode(data)
+    }
+
+    /// Return the original value if `encrypted` was encrypted with any of the
+    /// keys and has not been altered.
+    pub fn decrypt(&self, name: &str, encrypted: &str) -> Option<String> {
+        let data = URL_SAFE_NO_PAD.decode(encrypted).ok()?;
+        if data.len() < NONCE_LENGTH {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LENGTH);
+        self.keys.iter().find_map(|key| {
+            let cipher = Aes256Gcm::new_from_slice(&key.encryption).unwrap();
+            cipher.decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: name.as_bytes() }).ok()
+        }).and_then(|plaintext| String::from_utf8(plaintext).ok())
+    }
+}
+
+fn signature(key: &DerivedKey, name: &str, value: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(&key.signing).unwrap();
+    mac.update(name.as_bytes());
+    mac.update(b"=");
+    mac.update(value.as_bytes());
+    mac
+}
+
+fn signature_verified(key: &DerivedKey, name: &str, value: &str, signature: &[u8]) -> bool {
+    signature(key, name, value).verify_slice(signature).is_ok()
+}
+
+impl std::fmt::Debug for CookieKeys {
+
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CookieKeys").field("count", &self.keys.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CookieKeys;
+
+    #[test]
+    fn keys_are_required() {
+        assert!(CookieKeys::new(Vec::<&str>::new()).is_err());
+        assert!(CookieKeys::new([""]).is_err());
+    }
+
+    #[test]
+    fn signed_values_are_verified() {
+        let keys = CookieKeys::new(["secret"]).unwrap();
+        let signed = keys.sign("user", "42");
+        assert_eq!(keys.verify("user", &signed), Some("42".to_owned()));
+    }
+
+    #[test]
+    fn tampered_or_moved_signed_values_are_rejected() {
+        let keys = CookieKeys::new(["secret"]).unwrap();
+        let signed = keys.sign("user", "42");
+        assert_eq!(keys.verify("user", &signed.replacen("42", "43", 1)), None);
+        assert_eq!(keys.verify("admin", &signed), None);
+        assert_eq!(keys.verify("user", "42"), None);
+    }
+
+    #[test]
+    fn encrypted_values_are_decrypted() {
+        let keys = CookieKeys::new(["secret"]).unwrap();
+        let encrypted = keys.encrypt("cart", "a,b,c");
+        assert!(!encrypted.contains("a,b,c"));
+        assert_eq!(keys.decrypt("cart", &encrypted), Some("a,b,c".to_owned()));
+        assert_eq!(keys.decrypt("other", &encrypted), None);
+        assert_eq!(keys.decrypt("cart", "garbage"), None);
+    }
+
+    #[test]
+    fn older_keys_stay_readable_after_rotation() {
+        let old = CookieKeys::new(["old"]).unwrap();
+        let rotated = CookieKeys::new(["new", "old"]).unwrap();
+        let signed = old.sign("user", "42");
+        let encrypted = old.encrypt("cart", "a");
+        assert_eq!(rotated.verify("user", &signed), Some("42".to_owned()));
+        assert_eq!(rotated.decrypt("cart", &encrypted), Some("a".to_owned()));
+        let new = CookieKeys::new(["new"]).unwrap();
+        assert_eq!(new.verify("user", &signed), None);
+        assert_eq!(new.decrypt("cart", &encrypted), None);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod keys;
+
+pub use keys::CookieKeys;
\ No newline at end of file
@@ -0,0 +1,5 @@
+pub mod value;
+pub mod resolve;
+pub mod schema;
+
+pub use schema::build_graphql_schema;
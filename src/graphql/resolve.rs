@@ -0,0 +1,168 @@
+use async_graphql::dynamic::FieldValue;
+use indexmap::{indexmap, IndexMap};
+use key_path::path;
+use serde_json::{json, Value as JsonValue};
+use teo_result::Error;
+use crate::action::Action;
+use crate::action::action::*;
+use crate::graphql::value::graphql_error;
+use crate::handler::default;
+use crate::handler::input::{validate_and_transform_json_input_for_builtin_action, validate_and_transform_json_input_for_handler};
+use crate::handler::r#match::HandlerMatch;
+use crate::middleware::middleware::join_middleware;
+use crate::model::{Model, Object};
+use crate::request;
+use crate::response::Response;
+use crate::value::Value;
+
+/// A model record resolved by a GraphQL field. Its readable fields come from
+/// `to_teon`, so the model's read rules and `canRead` apply.
+pub(crate) struct Record {
+    pub(crate) model: &'static Model,
+    pub(crate) object: Option<Object>,
+    pub(crate) teon: Value,
+}
+
+impl Record {
+
+    async fn from_object(object: Object) -> async_graphql::Result<Self> {
+        let teon = object.to_teon().await.map_err(graphql_error)?;
+        Ok(Self { model: object.model(), object: Some(object), teon })
+    }
+
+    /// The object behind this record. Records returned by mutations only
+    /// have their output, so the object is fetched by its identifier.
+    async fn object(&self, req_ctx: &request::Ctx) -> async_graphql::Result<Object> {
+        if let Some(object) = &self.object {
+            return Ok(object.clone());
+        }
+        let mut identifier = IndexMap::new();
+        let Some(primary_index) = self.model.primary_index() else {
+            return Err(async_graphql::Error::new("record has no primary index"));
+        };
+        for item in primary_index.items() {
+            let Some(value) = self.teon.get(item.field.as_str()) else {
+                return Err(async_graphql::Error::new("record identifier is not readable"));
+            };
+            identifier.insert(item.field.clone(), value.clone());
+        }
+        let finder = Value::Dictionary(indexmap! { "where".to_owned() => Value::Dictionary(identifier) });
+        let object = req_ctx.transaction_ctx().find_unique_internal(self.model, &finder, true, FIND | SINGLE | ENTRY, Some(req_ctx.clone()), path![]).await.map_err(graphql_error)?;
+        object.ok_or_else(|| graphql_error(Error::not_found()))
+    }
+}
+
+pub(crate) fn model_at_path(req_ctx: &request::Ctx, model_path: &Vec<String>) -> async_graphql::Result<&'static Model> {
+    req_ctx.namespace().model_at_path(&model_path.iter().map(|s| s.as_str()).collect()).ok_or_else(|| async_graphql::Error::new("model is not found"))
+}
+
+fn action_ctx(req_ctx: &request::Ctx, model: &Model, action: Action, body: Value) -> request::Ctx {
+    req_ctx.derive(body, HandlerMatch {
+        path: model.path.clone(),
+        name: action.as_handler_str().to_owned(),
+        captures: IndexMap::new(),
+    })
+}
+
+fn response_data(response: Response) -> Value {
+    response.body().as_teon().and_then(|body| body.get("data")).cloned().unwrap_or(Value::Null)
+}
+
+/// Run one of a model's builtin actions. `args` is validated like the body
+/// of the matching REST action.
+pub(crate) async fn resolve_builtin(req_ctx: &request::Ctx, model: &'static Model, action: Action, args: Option<JsonValue>) -> async_graphql::Result<Option<FieldValue<'static>>> {
+    let args = args.unwrap_or(json!({}));
+    let body = validate_and_transform_json_input_for_builtin_action(model, action, &args, req_ctx.namespace()).map_err(graphql_error)?;
+    let transaction_ctx = req_ctx.transaction_ctx();
+    Ok(match action {
+        FIND_MANY_HANDLER => {
+            let objects = transaction_ctx.find_many_internal(model, &body, false, FIND | MANY | ENTRY, Some(req_ctx.clone()), path![]).await.map_err(graphql_error)?;
+            let mut records = vec![];
+            for object in objects {
+                records.push(FieldValue::owned_any(Record::from_object(object).await?));
+            }
+            Some(FieldValue::list(records))
+        }
+        FIND_UNIQUE_HANDLER | FIND_FIRST_HANDLER => {
+            let object = if action == FIND_UNIQUE_HANDLER {
+                transaction_ctx.find_unique_internal(model, &body, false, FIND | SINGLE | ENTRY, Some(req_ctx.clone()), path![]).await
+            } else {
+                transaction_ctx.find_first_internal(model, &body, false, FIND | SINGLE | ENTRY, Some(req_ctx.clone()), path![]).await
+            }.map_err(graphql_error)?;
+            match object {
+                Some(object) => Some(FieldValue::owned_any(Record::from_object(object).await?)),
+                None => None,
+            }
+        }
+        COUNT_HANDLER | AGGREGATE_HANDLER | GROUP_BY_HANDLER => {
+            let ctx = action_ctx(req_ctx, model, action, body);
+            let response = match action {
+                COUNT_HANDLER => default::count(&ctx).await,
+                AGGREGATE_HANDLER => default::aggregate(&ctx).await,
+                _ => default::group_by(&ctx).await,
+            }.map_err(graphql_error)?;
+            Some(FieldValue::value(crate::graphql::value::teon_to_graphql(&response_data(response))))
+        }
+        CREATE_HANDLER | UPDATE_HANDLER | UPSERT_HANDLER | DELETE_HANDLER => {
+            let ctx = action_ctx(req_ctx, model, action, body);
+            let response = match action {
+                CREATE_HANDLER => default::create(&ctx).await,
+                UPDATE_HANDLER => default::update(&ctx).await,
+                UPSERT_HANDLER => default::upsert(&ctx).await,
+                _ => default::delete(&ctx).await,
+            }.map_err(graphql_error)?;
+            Some(FieldValue::owned_any(Record { model, object: None, teon: response_data(response) }))
+        }
+        _ => None,
+    })
+}
+
+/// Resolve a relation of a record. `args` is validated as the related
+/// model's findMany arguments for to-many relations.
+pub(crate) async fn resolve_relation(req_ctx: &request::Ctx, record: &Record, name: &str, args: Option<JsonValue>) -> async_graphql::Result<Option<FieldValue<'static>>> {
+    let relation = record.model.relation(name).ok_or_else(|| async_graphql::Error::new("relation is not found"))?;
+    let related = model_at_path(req_ctx, &relation.model)?;
+    let object = record.object(req_ctx).await?;
+    if relation.is_vec {
+        let finder = match args {
+            Some(args) => Some(validate_and_transform_json_input_for_builtin_action(related, FIND_MANY_HANDLER, &args, req_ctx.namespace()).map_err(graphql_error)?),
+            None => None,
+        };
+        let objects = object.fetch_relation_objects(name, finder.as_ref()).await.map_err(graphql_error)?;
+        let mut records = vec![];
+        for object in objects {
+            records.push(FieldValue::owned_any(Record::from_object(object).await?));
+        }
+        Ok(Some(FieldValue::list(records)))
+    } else {
+        match object.fetch_relation_object(name, None).await.map_err(graphql_error)? {
+            Some(object) => Ok(Some(FieldValue::owned_any(Record::from_object(object).await?))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Run a custom handler with `input` as its json body. The namespace stack
+/// has run for the GraphQL endpoint, the handler runs through its group's
+/// stack and its own stack like a REST request.
+pub(crate) async fn resolve_handler(req_ctx: &request::Ctx, handler_path: &Vec<String>, input: Option<JsonValue>) -> async_graphql::Result<Option<FieldValue<'static>>> {
+    let handler_path_ref: Vec<&str> = handler_path.iter().map(|s| s.as_str()).collect();
+    let handler = req_ctx.namespace().handler_at_path(&handler_path_ref).ok_or_else(|| async_graphql::Error::new("handler is not found"))?;
+    let parent_path: Vec<&str> = handler_path_ref[..handler_path_ref.len() - 1].to_vec();
+    let stack = match req_ctx.namespace().namespace_at_path(&parent_path) {
+        Some(_) => handler.middleware_stack,
+        None => match req_ctx.namespace().handler_group_at_path(&parent_path) {
+            Some(group) => join_middleware(group.middleware_stack, handler.middleware_stack),
+            None => handler.middleware_stack,
+        },
+    };
+    let input = input.unwrap_or(JsonValue::Null);
+    let body = validate_and_transform_json_input_for_handler(handler, &input, req_ctx.namespace()).map_err(graphql_error)?;
+    let ctx = req_ctx.derive(body, HandlerMatch {
+        path: handler_path[..handler_path.len() - 1].to_vec(),
+        name: handler_path.last().unwrap().clone(),
+        captures: IndexMap::new(),
+    });
+    let response = stack.call(ctx, handler.call).await.map_err(graphql_error)?;
+    Ok(Some(FieldValue::value(crate::graphql::value::teon_to_graphql(&response.body().as_teon().cloned().unwrap_or(Value::Null)))))
+}
@@ -0,0 +1,270 @@
+use async_graphql::dynamic::{Enum, EnumItem, Field, FieldFuture, FieldValue, InputValue, Object as GraphQLObject, ResolverContext, Scalar, Schema, TypeRef};
+use async_graphql::{Name, Value as GraphQLValue};
+use serde_json::Value as JsonValue;
+use teo_parser::r#type::Type;
+use teo_result::{Error, Result};
+use crate::action::Action;
+use crate::action::action::*;
+use crate::graphql::resolve::{model_at_path, resolve_builtin, resolve_handler, resolve_relation, Record};
+use crate::graphql::value::{graphql_to_json, teon_to_graphql};
+use crate::handler::handler::Method;
+use crate::model::Model;
+use crate::namespace::Namespace;
+use crate::r#enum::Enum as TeoEnum;
+use crate::request;
+
+const JSON: &str = "JSON";
+const CUSTOM_SCALARS: [&str; 6] = [JSON, "Int64", "Decimal", "Date", "DateTime", "ObjectId"];
+
+const QUERY_ACTIONS: [Action; 6] = [FIND_UNIQUE_HANDLER, FIND_FIRST_HANDLER, FIND_MANY_HANDLER, COUNT_HANDLER, AGGREGATE_HANDLER, GROUP_BY_HANDLER];
+const MUTATION_ACTIONS: [Action; 4] = [CREATE_HANDLER, UPDATE_HANDLER, UPSERT_HANDLER, DELETE_HANDLER];
+
+/// Build a GraphQL schema from the models, relations, enums and custom
+/// handlers of `main_namespace`.
+///
+/// Every model gets an object type, query fields for its find, count,
+/// aggregate and groupBy actions and mutation fields for create, update,
+/// upsert and delete, all named like `postFindMany`. Action arguments are
+/// passed as a single `args` JSON value with the shape of the REST action's
+/// body. Custom handlers take a JSON `input` and return JSON, GET handlers
+/// are queries and the others mutations. Resolvers expect a `request::Ctx`
+/// in the request data.
+pub fn build_graphql_schema(main_namespace: &Namespace) -> Result<Schema> {
+    let mut query = GraphQLObject::new("Query");
+    let mut mutation = GraphQLObject::new("Mutation");
+    let mut builder_types: Vec<GraphQLObject> = vec![];
+    let mut enums: Vec<Enum> = vec![];
+    for e in collect_enums(main_namespace) {
+        enums.push(e.members().iter().fold(Enum::new(type_name(&e.path)), |graphql_enum, member| graphql_enum.item(EnumItem::new(member.name.as_str()))));
+    }
+    let models = collect_models(main_namespace);
+    for model in &models {
+        builder_types.push(model_object(model, main_namespace));
+        for action in model.builtin_handlers.iter() {
+            if QUERY_ACTIONS.contains(action) {
+                query = query.field(builtin_field(model, *action));
+            } else if MUTATION_ACTIONS.contains(action) {
+                mutation = mutation.field(builtin_field(model, *action));
+            }
+        }
+    }
+    let mut has_mutation = models.iter().any(|m| m.builtin_handlers.iter().any(|a| MUTATION_ACTIONS.contains(a)));
+    for handler in main_namespace.collect_handlers() {
+        if handler.method == Method::Get {
+            query = query.field(handler_field(handler.path.clone()));
+        } else {
+            mutation = mutation.field(handler_field(handler.path.clone()));
+            has_mutation = true;
+        }
+    }
+    let mut builder = Schema::build("Query", if has_mutation { Some("Mutation") } else { None }, None).register(query);
+    if has_mutation {
+        builder = builder.register(mutation);
+    }
+    for scalar in CUSTOM_SCALARS {
+        builder = builder.register(Scalar::new(scalar));
+    }
+    for graphql_enum in enums {
+        builder = builder.register(graphql_enum);
+    }
+    for object in builder_types {
+        builder = builder.register(object);
+    }
+    builder.finish().map_err(|e| Error::new(format!("cannot build graphql schema: {}", e)))
+}
+
+fn collect_models(namespace: &Namespace) -> Vec<&Model> {
+    let mut result: Vec<&Model> = namespace.models.values().collect();
+    for child in namespace.namespaces.values() {
+        result.extend(collect_models(child));
+    }
+    result
+}
+
+fn collect_enums(namespace: &Namespace) -> Vec<&TeoEnum> {
+    let mut result: Vec<&TeoEnum> = namespace.enums.values().filter(|e| !e.interface).collect();
+    for child in namespace.namespaces.values() {
+        result.extend(collect_enums(child));
+    }
+    result
+}
+
+fn type_name(path: &Vec<String>) -> String {
+    path.join("_")
+}
+
+fn field_name(path: &Vec<String>, suffix: &str) -> String {
+    let mut name = path.join("_");
+    if let Some(first) = name.get(0..1) {
+        name = first.to_lowercase() + &name[1..];
+    }
+    match suffix.get(0..1) {
+        Some(first) => name + &first.to_uppercase() + &suffix[1..],
+        None => name,
+    }
+}
+
+/// The GraphQL type of a field's value. Fields are nullable since read
+/// rules may leave them out of a record.
+fn type_ref(t: &Type, main_namespace: &Namespace) -> TypeRef {
+    match t {
+        Type::Optional(inner) => type_ref(inner.as_ref(), main_namespace),
+        Type::Array(inner) => TypeRef::List(Box::new(TypeRef::NonNull(Box::new(type_ref(inner.as_ref(), main_namespace))))),
+        t => TypeRef::named(named_type(t, main_namespace)),
+    }
+}
+
+fn named_type(t: &Type, main_namespace: &Namespace) -> String {
+    match t {
+        Type::Bool => TypeRef::BOOLEAN.to_owned(),
+        Type::Int => TypeRef::INT.to_owned(),
+        Type::Float32 | Type::Float => TypeRef::FLOAT.to_owned(),
+        Type::String => TypeRef::STRING.to_owned(),
+        Type::Int64 => "Int64".to_owned(),
+        Type::Decimal => "Decimal".to_owned(),
+        Type::Date => "Date".to_owned(),
+        Type::DateTime => "DateTime".to_owned(),
+        Type::ObjectId => "ObjectId".to_owned(),
+        Type::EnumVariant(reference) => match main_namespace.enum_at_path(&reference.str_path()) {
+            Some(e) if !e.interface => type_name(&e.path),
+            _ => JSON.to_owned(),
+        },
+        _ => JSON.to_owned(),
+    }
+}
+
+fn is_enum(t: &Type) -> bool {
+    match t {
+        Type::Optional(inner) | Type::Array(inner) => is_enum(inner.as_ref()),
+        Type::EnumVariant(_) => true,
+        _ => false,
+    }
+}
+
+fn model_object(model: &Model, main_namespace: &Namespace) -> GraphQLObject {
+    let mut object = GraphQLObject::new(type_name(&model.path));
+    if let Some(desc) = model.comment.as_ref().and_then(|c| c.desc.as_ref()) {
+        object = object.description(desc);
+    }
+    for field in model.fields() {
+        if field.read.is_no_read() {
+            continue
+        }
+        object = object.field(value_field(&field.name, type_ref(&field.r#type, main_namespace), is_enum(&field.r#type)));
+    }
+    for property in model.properties() {
+        object = object.field(value_field(&property.name, type_ref(&property.r#type, main_namespace), is_enum(&property.r#type)));
+    }
+    for relation in model.relations() {
+        let related = TypeRef::named_nn(type_name(&relation.model));
+        let relation_type = if relation.is_vec { TypeRef::NonNull(Box::new(TypeRef::List(Box::new(related)))) } else { TypeRef::named(type_name(&relation.model)) };
+        let name = relation.name.clone();
+        let mut field = Field::new(relation.name.as_str(), relation_type, move |ctx| {
+            let name = name.clone();
+            FieldFuture::new(async move {
+                let req_ctx = ctx.data::<request::Ctx>()?;
+                let record = ctx.parent_value.try_downcast_ref::<Record>()?;
+                let args = json_argument(&ctx, "args")?;
+                resolve_relation(req_ctx, record, &name, args).await
+            })
+        });
+        if relation.is_vec {
+            field = field.argument(InputValue::new("args", TypeRef::named(JSON)));
+        }
+        object = object.field(field);
+    }
+    object
+}
+
+fn value_field(name: &str, type_ref: TypeRef, is_enum: bool) -> Field {
+    let name = name.to_owned();
+    Field::new(name.as_str(), type_ref, move |ctx| {
+        let name = name.clone();
+        FieldFuture::new(async move {
+            let record = ctx.parent_value.try_downcast_ref::<Record>()?;
+            Ok(record.teon.get(name.as_str()).map(|value| FieldValue::value(if is_enum {
+                enum_value(teon_to_graphql(value))
+            } else {
+                teon_to_graphql(value)
+            })))
+        })
+    })
+}
+
+fn enum_value(value: GraphQLValue) -> GraphQLValue {
+    match value {
+        GraphQLValue::String(s) => GraphQLValue::Enum(Name::new(s)),
+        GraphQLValue::List(list) => GraphQLValue::List(list.into_iter().map(enum_value).collect()),
+        value => value,
+    }
+}
+
+fn builtin_field(model: &Model, action: Action) -> Field {
+    let record = type_name(&model.path);
+    let output = match action {
+        FIND_MANY_HANDLER => TypeRef::named_nn_list_nn(record),
+        FIND_UNIQUE_HANDLER | FIND_FIRST_HANDLER => TypeRef::named(record),
+        COUNT_HANDLER => TypeRef::named_nn(TypeRef::INT),
+        AGGREGATE_HANDLER | GROUP_BY_HANDLER => TypeRef::named(JSON),
+        _ => TypeRef::named_nn(record),
+    };
+    let args_type = match action {
+        FIND_MANY_HANDLER | FIND_FIRST_HANDLER | COUNT_HANDLER => TypeRef::named(JSON),
+        _ => TypeRef::named_nn(JSON),
+    };
+    let model_path = model.path.clone();
+    Field::new(field_name(&model.path, action.as_handler_str()), output, move |ctx| {
+        let model_path = model_path.clone();
+        FieldFuture::new(async move {
+            let req_ctx = ctx.data::<request::Ctx>()?;
+            let model = model_at_path(req_ctx, &model_path)?;
+            let args = json_argument(&ctx, "args")?;
+            resolve_builtin(req_ctx, model, action, args).await
+        })
+    }).argument(InputValue::new("args", args_type))
+}
+
+fn handler_field(handler_path: Vec<String>) -> Field {
+    Field::new(field_name(&handler_path, ""), TypeRef::named(JSON), move |ctx| {
+        let handler_path = handler_path.clone();
+        FieldFuture::new(async move {
+            let req_ctx = ctx.data::<request::Ctx>()?;
+            let input = json_argument(&ctx, "input")?;
+            resolve_handler(req_ctx, &handler_path, input).await
+        })
+    }).argument(InputValue::new("input", TypeRef::named(JSON)))
+}
+
+fn json_argument(ctx: &ResolverContext, name: &str) -> async_graphql::Result<Option<JsonValue>> {
+    match ctx.args.get(name) {
+        Some(value) if !value.is_null() => Ok(Some(graphql_to_json(value.as_value())?)),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handler::handler::Method;
+    use crate::namespace::Namespace;
+    use crate::response::Response;
+    use super::build_graphql_schema;
+
+    #[test]
+    fn includes_handlers_of_namespaces_and_groups() {
+        let mut namespace = Namespace::main();
+        namespace.define_handler("ping", || async { Ok(Response::empty()) });
+        namespace.define_handler("status", || async { Ok(Response::empty()) });
+        namespace.handlers.get_mut("status").unwrap().method = Method::Get;
+        namespace.define_handler_group("users", |group| group.define_handler("list", || async { Ok(Response::empty()) }));
+        namespace.namespace_mut_or_create_at_path(&vec!["admin"]).define_handler("stats", || async { Ok(Response::empty()) });
+        let sdl = build_graphql_schema(&namespace).unwrap().sdl();
+        let query = &sdl[sdl.find("type Query").unwrap()..];
+        let query = &query[..query.find('}').unwrap()];
+        assert!(query.contains("status("));
+        let mutation = &sdl[sdl.find("type Mutation").unwrap()..];
+        let mutation = &mutation[..mutation.find('}').unwrap()];
+        for field in ["ping(", "users_list(", "admin_stats("] {
+            assert!(mutation.contains(field), "{}", field);
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use async_graphql::{ErrorExtensions, Value as GraphQLValue};
+use chrono::SecondsFormat;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use teo_result::Error;
+use crate::value::Value;
+
+/// Convert a teon value into a GraphQL output value. Types without a
+/// GraphQL counterpart are written as strings.
+pub fn teon_to_graphql(value: &Value) -> GraphQLValue {
+    match value {
+        Value::Null => GraphQLValue::Null,
+        Value::Bool(b) => GraphQLValue::Boolean(*b),
+        Value::Int(i) => GraphQLValue::from(*i),
+        Value::Int64(i) => GraphQLValue::from(*i),
+        Value::Float32(f) => GraphQLValue::from(*f as f64),
+        Value::Float(f) => GraphQLValue::from(*f),
+        Value::Decimal(d) => GraphQLValue::String(d.normalized().to_string()),
+        Value::ObjectId(o) => GraphQLValue::String(o.to_hex()),
+        Value::String(s) => GraphQLValue::String(s.clone()),
+        Value::Date(d) => GraphQLValue::String(d.format("%Y-%m-%d").to_string()),
+        Value::DateTime(d) => GraphQLValue::String(d.to_rfc3339_opts(SecondsFormat::Millis, true)),
+        Value::Array(a) => GraphQLValue::List(a.iter().map(teon_to_graphql).collect()),
+        Value::Dictionary(d) => {
+            let map: JsonMap<String, JsonValue> = d.iter().map(|(k, v)| (k.clone(), teon_to_graphql(v).into_json().unwrap_or(JsonValue::Null))).collect();
+            GraphQLValue::from_json(JsonValue::Object(map)).unwrap_or(GraphQLValue::Null)
+        }
+        value => match JsonValue::try_from(value) {
+            Ok(json) => GraphQLValue::from_json(json).unwrap_or(GraphQLValue::Null),
+            Err(_) => GraphQLValue::Null,
+        },
+    }
+}
+
+/// Convert a GraphQL argument into json, which is then validated like a
+/// request body.
+pub fn graphql_to_json(value: &GraphQLValue) -> async_graphql::Result<JsonValue> {
+    value.clone().into_json().map_err(|e| async_graphql::Error::new(e.to_string()))
+}
+
+pub fn graphql_error(error: Error) -> async_graphql::Error {
+    async_graphql::Error::new(error.message.clone()).extend_with(|_, extensions| {
+        extensions.set("code", error.code as i32);
+        if let Some(errors) = &error.errors {
+            let map: JsonMap<String, JsonValue> = errors.iter().map(|(k, v)| (k.to_string(), JsonValue::String(v.to_string()))).collect();
+            extensions.set("errors", GraphQLValue::from_json(JsonValue::Object(map)).unwrap_or(GraphQLValue::Null));
+        }
+    })
+}
@@ -0,0 +1,166 @@
+use indexmap::IndexMap;
+use key_path::{path, KeyPath};
+use serde_json::{json, Value as JsonValue};
+use teo_result::{Error, Result};
+use crate::action::Action;
+use crate::action::action::*;
+use crate::coder::type_tag::strip_type_tags;
+use crate::connection::transaction;
+use crate::handler::default;
+use crate::handler::input::validate_and_transform_json_input_for_builtin_action;
+use crate::handler::r#match::HandlerMatch;
+use crate::request;
+use crate::response::Response;
+use crate::value::Value;
+
+/// Run the operations of a batch request in a single transaction.
+///
+/// The body is `{ "operations": [{ "id"?, "model", "action", "input"? }] }`
+/// where `model` is the dot separated model path and `action` is the name of
+/// one of the model's builtin actions. An input value may be a reference like
+/// `{ "$ref": "order.data.id" }`, which is replaced by a value from an earlier
+/// result. The first segment is the referenced operation's id or index, the
+/// rest is the path into its response body.
+///
+/// Results are returned in order. When an operation fails, everything is
+/// rolled back and its errors are pathed under `operations.<index>.input`.
+pub async fn execute_batch(req_ctx: &request::Ctx, body: &JsonValue) -> Result<Vec<Value>> {
+    let Some(operations) = body.get("operations").and_then(JsonValue::as_array) else {
+        return Err(Error::invalid_request_pathed(path!["operations"], "expect array"));
+    };
+    req_ctx.transaction_ctx().run_transaction(|ctx: transaction::Ctx| async move {
+        let mut ids: Vec<Option<&str>> = vec![];
+        let mut results: Vec<JsonValue> = vec![];
+        let mut values: Vec<Value> = vec![];
+        for (index, operation) in operations.iter().enumerate() {
+            let id = operation.get("id").and_then(JsonValue::as_str);
+            if let Some(id) = id {
+                if ids.contains(&Some(id)) {
+                    return Err(Error::invalid_request_pathed(path!["operations", index, "id"], "duplicated operation id"));
+                }
+            }
+            let value = execute_operation(req_ctx, &ctx, index, operation, &ids, &results).await?;
+            ids.push(id);
+            results.push(strip_type_tags(JsonValue::try_from(&value)?));
+            values.push(value);
+        }
+        Ok(values)
+    }).await
+}
+
+async fn execute_operation(req_ctx: &request::Ctx, transaction_ctx: &transaction::Ctx, index: usize, operation: &JsonValue, ids: &Vec<Option<&str>>, results: &Vec<JsonValue>) -> Result<Value> {
+    let operation_path = path!["operations", index];
+    let Some(model_name) = operation.get("model").and_then(JsonValue::as_str) else {
+        return Err(Error::invalid_request_pathed(&operation_path + "model", "expect string"));
+    };
+    let Some(model) = req_ctx.namespace().model_at_path(&model_name.split(".").collect()) else {
+        return Err(Error::not_found_pathed(&operation_path + "model", format!("model '{}' is not found", model_name)));
+    };
+    let Some(action_name) = operation.get("action").and_then(JsonValue::as_str) else {
+        return Err(Error::invalid_request_pathed(&operation_path + "action", "expect string"));
+    };
+    let Some(action) = Action::from_handler_str(action_name).filter(|action| model.builtin_handlers.contains(action)) else {
+        return Err(Error::invalid_request_pathed(&operation_path + "action", format!("action '{}' is not available", action_name)));
+    };
+    let input = resolve_references(operation.get("input").cloned().unwrap_or(json!({})), &(&operation_path + "input"), ids, results)?;
+    let body = validate_and_transform_json_input_for_builtin_action(model, action, &input, req_ctx.namespace()).map_err(|e| operation_error(index, e))?;
+    let action_ctx = req_ctx.derive_in_transaction(body, HandlerMatch {
+        path: model.path.clone(),
+        name: action.as_handler_str().to_owned(),
+        captures: IndexMap::new(),
+    }, transaction_ctx.clone());
+    let response = call_builtin_handler(&action_ctx, action).await.map_err(|e| operation_error(index, e))?;
+    Ok(response.body().as_teon().cloned().unwrap_or(Value::Null))
+}
+
+async fn call_builtin_handler(req_ctx: &request::Ctx, action: Action) -> Result<Response> {
+    match action {
+        FIND_UNIQUE_HANDLER => default::find_unique(req_ctx).await,
+        FIND_FIRST_HANDLER => default::find_first(req_ctx).await,
+        FIND_MANY_HANDLER => default::find_many(req_ctx).await,
+        CREATE_HANDLER => default::create(req_ctx).await,
+        UPDATE_HANDLER => default::update(req_ctx).await,
+        UPSERT_HANDLER => default::upsert(req_ctx).await,
+        COPY_HANDLER => default::copy(req_ctx).await,
+        DELETE_HANDLER => default::delete(req_ctx).await,
+        CREATE_MANY_HANDLER => default::create_many(req_ctx).await,
+        UPDATE_MANY_HANDLER => default::update_many(req_ctx).await,
+        COPY_MANY_HANDLER => default::copy_many(req_ctx).await,
+        DELETE_MANY_HANDLER => default::delete_many(req_ctx).await,
+        COUNT_HANDLER => default::count(req_ctx).await,
+        AGGREGATE_HANDLER => default::aggregate(req_ctx).await,
+        GROUP_BY_HANDLER => default::group_by(req_ctx).await,
+        _ => unreachable!(),
+    }
+}
+
+fn resolve_references(input: JsonValue, path: &KeyPath, ids: &Vec<Option<&str>>, results: &Vec<JsonValue>) -> Result<JsonValue> {
+    Ok(match input {
+        JsonValue::Object(map) => {
+            if map.len() == 1 {
+                if let Some(reference) = map.get("$ref").and_then(JsonValue::as_str) {
+                    return resolve_reference(reference, ids, results).ok_or_else(|| Error::invalid_request_pathed(path.clone(), format!("unresolved reference '{}'", reference)));
+                }
+            }
+            JsonValue::Object(map.into_iter().map(|(k, v)| Ok((k.clone(), resolve_references(v, &(path + k.as_str()), ids, results)?))).collect::<Result<_>>()?)
+        }
+        JsonValue::Array(array) => JsonValue::Array(array.into_iter().enumerate().map(|(i, v)| resolve_references(v, &(path + i), ids, results)).collect::<Result<_>>()?),
+        input => input,
+    })
+}
+
+fn resolve_reference(reference: &str, ids: &Vec<Option<&str>>, results: &Vec<JsonValue>) -> Option<JsonValue> {
+    let mut segments = reference.split(".");
+    let operation = segments.next()?;
+    let index = match ids.iter().position(|id| *id == Some(operation)) {
+        Some(index) => index,
+        None => operation.parse::<usize>().ok()?,
+    };
+    let mut value = results.get(index)?;
+    for segment in segments {
+        value = match value {
+            JsonValue::Array(array) => array.get(segment.parse::<usize>().ok()?)?,
+            JsonValue::Object(map) => map.get(segment)?,
+            _ => None?,
+        };
+    }
+    Some(value.clone())
+}
+
+fn operation_error(index: usize, mut error: Error) -> Error {
+    let prefix = format!("operations.{}.input", index);
+    error.errors = Some(match &error.errors {
+        Some(errors) => errors.iter().map(|(k, v)| (if k.is_empty() { prefix.clone() } else { format!("{}.{}", prefix, k) }, v.clone())).collect(),
+        None => [(prefix, error.message.clone())].into_iter().collect(),
+    });
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn resolves_references_by_id_and_index() {
+        let ids = vec![Some("order"), None];
+        let results = vec![json!({ "data": { "id": 7 } }), json!({ "data": [{ "id": 8 }] })];
+        let input = json!({ "create": { "orderId": { "$ref": "order.data.id" }, "items": [{ "$ref": "1.data.0.id" }] } });
+        assert_eq!(
+            resolve_references(input, &path!["input"], &ids, &results).unwrap(),
+            json!({ "create": { "orderId": 7, "items": [8] } }),
+        );
+    }
+
+    #[test]
+    fn reports_unresolved_references_with_path() {
+        let error = resolve_references(json!({ "a": { "$ref": "missing.id" } }), &path!["operations", 1, "input"], &vec![], &vec![]).unwrap_err();
+        assert!(error.errors.unwrap().contains_key("operations.1.input.a"));
+    }
+
+    #[test]
+    fn prefixes_operation_errors() {
+        let error = operation_error(2, Error::invalid_request_pathed(path!["create", "name"], "required"));
+        assert_eq!(error.errors.unwrap().get("operations.2.input.create.name").unwrap(), "required");
+    }
+}
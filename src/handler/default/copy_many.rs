@@ -10,7 +10,7 @@ use crate::model::object::object::ErrorIfNotFound;
 pub async fn copy_many(req_ctx: &request::Ctx) -> teo_result::Result<Response> {
     let model = req_ctx.namespace().model_at_path(&req_ctx.handler_match().path()).unwrap();
     let action = COPY | MANY | ENTRY;
-    let (retval, count) = req_ctx.transaction_ctx().run_transaction(|ctx: transaction::Ctx| async move {
+    let (retval, count) = req_ctx.run_transaction(|ctx: transaction::Ctx| async move {
         let objects = ctx.find_many_internal(model, req_ctx.body(), true, action, Some(req_ctx.clone()), path![]).await?;
         let copy = req_ctx.body().get("copy");
         let include = req_ctx.body().get("include");
@@ -9,7 +9,7 @@ use crate::connection::transaction;
 pub async fn delete_many(req_ctx: &request::Ctx) -> teo_result::Result<Response> {
     let model = req_ctx.namespace().model_at_path(&req_ctx.handler_match().path()).unwrap();
     let action = DELETE | MANY | ENTRY;
-    let (objects, count) = req_ctx.transaction_ctx().run_transaction(|ctx: transaction::Ctx| async move {
+    let (objects, count) = req_ctx.run_transaction(|ctx: transaction::Ctx| async move {
         let objects = ctx.find_many_internal(model, req_ctx.body(), true, action, Some(req_ctx.clone()), path![]).await?;
         let mut count = 0;
         let mut ret_data: Vec<Value> = vec![];
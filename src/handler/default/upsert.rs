@@ -10,7 +10,7 @@ use crate::handler::default::internal::update::update_internal;
 pub async fn upsert(req_ctx: &request::Ctx) -> teo_result::Result<Response> {
     let model = req_ctx.namespace().model_at_path(&req_ctx.handler_match().path()).unwrap();
     let action = UPSERT | SINGLE | ENTRY;
-    let value: Value = req_ctx.transaction_ctx().run_transaction(|ctx: transaction::Ctx| async move {
+    let value: Value = req_ctx.run_transaction(|ctx: transaction::Ctx| async move {
         let find_result = ctx.find_unique_internal(model, req_ctx.body(), true, action, Some(req_ctx.clone()), path![]).await?;
         let include = req_ctx.body().get("include");
         let select = req_ctx.body().get("select");
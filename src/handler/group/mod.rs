@@ -1,22 +1,48 @@
+pub mod decorator;
+
 use std::collections::BTreeMap;
+use educe::Educe;
 use serde::Serialize;
 use teo_parser::ast::handler::HandlerInputFormat;
 use teo_parser::r#type::Type;
+use crate::arguments::Arguments;
 use crate::handler::ctx_argument::HandlerCtxArgument;
 use crate::handler::Handler;
 use crate::handler::handler::Method;
+use crate::middleware::middleware::{empty_middleware, Middleware};
 use crate::request;
 use crate::traits::named::Named;
 use crate::utils::next_path;
 
-#[derive(Serialize, Debug)]
+#[derive(Educe, Serialize)]
+#[educe(Debug)]
 pub struct Group {
     pub path: Vec<String>,
     pub handlers: BTreeMap<String, Handler>,
+    pub middleware_uses: Vec<(Vec<String>, Arguments)>,
+    /// The group's own middlewares. It runs inside the namespace stack and outside the
+    /// stacks of the group's handlers.
+    #[serde(skip)] #[educe(Debug(ignore))]
+    pub middleware_stack: &'static dyn Middleware,
 }
 
 impl Group {
 
+    pub fn new(path: Vec<String>) -> Self {
+        Self {
+            path,
+            handlers: BTreeMap::new(),
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
+        }
+    }
+
+    /// Attach a middleware to every handler of this group. The middleware is resolved by its
+    /// path and runs inside the namespace middlewares and outside the handler middlewares.
+    pub fn use_middleware(&mut self, path: Vec<&str>, arguments: Arguments) {
+        self.middleware_uses.push((path.iter().map(|s| s.to_string()).collect(), arguments));
+    }
+
     pub fn define_handler<T, F>(&mut self, name: &str, call: F) where T: 'static, F: 'static + HandlerCtxArgument<T> {
         let wrapped_call = Box::leak(Box::new(call));
         let handler = Handler {
@@ -31,9 +57,12 @@ impl Group {
             format: HandlerInputFormat::Json,
             path: next_path(&self.path, name),
             ignore_prefix: false,
+            timeout: None,
             method: Method::Post,
             interface: None,
             url: None,
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
             call: Box::leak(Box::new(|ctx: request::Ctx| async {
                 wrapped_call.call(ctx).await
             })),
@@ -47,3 +76,6 @@ impl Named for Group {
         self.path.last().unwrap().as_str()
     }
 }
+
+unsafe impl Send for Group { }
+unsafe impl Sync for Group { }
@@ -1,7 +1,10 @@
+use std::time::Duration;
 use educe::Educe;
 use serde::Serialize;
 use teo_parser::ast::handler::HandlerInputFormat;
 use teo_parser::r#type::Type;
+use crate::arguments::Arguments;
+use crate::middleware::middleware::Middleware;
 use crate::middleware::next::Next;
 use crate::model::Model;
 use crate::traits::named::Named;
@@ -43,6 +46,13 @@ pub struct Handler {
     pub url: Option<String>,
     pub interface: Option<String>,
     pub ignore_prefix: bool,
+    /// The handler is cancelled with 504 when it runs longer than this.
+    pub timeout: Option<Duration>,
+    pub middleware_uses: Vec<(Vec<String>, Arguments)>,
+    /// The handler's own middlewares. It runs inside the namespace stack and the group stack,
+    /// its `next` is `call`.
+    #[serde(skip)] #[educe(Debug(ignore))]
+    pub middleware_stack: &'static dyn Middleware,
     #[serde(skip)] #[educe(Debug(ignore))]
     pub call: &'static dyn Next,
 }
@@ -61,6 +71,12 @@ impl Handler {
         !(self.method == Method::Get || self.method == Method::Delete)
     }
 
+    /// Attach a middleware to this handler only. The middleware is resolved by its path and
+    /// runs inside the namespace and group middlewares.
+    pub fn use_middleware(&mut self, path: Vec<&str>, arguments: Arguments) {
+        self.middleware_uses.push((path.iter().map(|s| s.to_string()).collect(), arguments));
+    }
+
     pub fn custom_url_args_path(&self) -> Option<Vec<String>> {
         if let Some(interface) = &self.interface {
             let mut result = self.path.clone();
@@ -78,3 +94,6 @@ impl Named for Handler {
         self.path.last().map(|s| s.as_str()).unwrap()
     }
 }
+
+unsafe impl Send for Handler { }
+unsafe impl Sync for Handler { }
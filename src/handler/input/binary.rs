@@ -0,0 +1,11 @@
+use key_path::path;
+use teo_result::Result;
+use crate::coder::binary::{binary_to_teon_with_type, BinaryFormat};
+use crate::handler::Handler;
+use crate::namespace::Namespace;
+use crate::value::Value;
+
+/// The input of json handlers whose body is sent as MessagePack or CBOR.
+pub fn validate_and_transform_binary_input_for_handler(handler: &Handler, body: &[u8], format: BinaryFormat, main_namespace: &Namespace) -> Result<Value> {
+    binary_to_teon_with_type(body, format, &path![], &handler.input_type, main_namespace)
+}
@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+use bytes::Bytes;
+use futures_util::Stream;
+use indexmap::IndexMap;
+use key_path::path;
+use multer::{Constraints, Multipart, SizeLimit};
+use teo_result::{Error, Result};
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+use crate::coder::form_to_teon::{form_to_teon_with_type, insert_form_value};
+use crate::handler::Handler;
+use crate::namespace::Namespace;
+use crate::value::file::File;
+use crate::value::Value;
+
+#[derive(Debug, Clone)]
+pub struct MultipartOptions {
+    pub temp_dir: PathBuf,
+    pub max_file_size: Option<u64>,
+    pub max_request_size: Option<u64>,
+}
+
+impl Default for MultipartOptions {
+
+    /// Files up to 10 MiB in requests up to 50 MiB.
+    fn default() -> Self {
+        Self {
+            temp_dir: std::env::temp_dir().join("teo"),
+            max_file_size: Some(10 * 1024 * 1024),
+            max_request_size: Some(50 * 1024 * 1024),
+        }
+    }
+}
+
+/// Decode a multipart/form-data body into a dictionary. Text parts become
+/// strings and file parts are written into the temp directory as files.
+///
+/// When decoding fails, every file written for the request is removed. Once
+/// the form is no longer needed, call `remove_form_files` to remove them.
+pub async fn parse_multipart_form<S, O, E>(content_type: &str, body: S, options: &MultipartOptions) -> Result<Value> where
+    S: Stream<Item = std::result::Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+{
+    let mut written = vec![];
+    let result = read_multipart_form(content_type, body, options, &mut written).await;
+    if result.is_err() {
+        remove_files(written.iter()).await;
+    }
+    result
+}
+
+/// Remove the uploaded files of a form returned by `parse_multipart_form`.
+pub async fn remove_form_files(form: &Value) {
+    let mut paths = vec![];
+    collect_form_files(form, &mut paths);
+    remove_files(paths.into_iter()).await;
+}
+
+fn collect_form_files<'a>(value: &'a Value, paths: &mut Vec<&'a str>) {
+    match value {
+        Value::File(file) => paths.push(file.filepath.as_str()),
+        Value::Array(array) => array.iter().for_each(|v| collect_form_files(v, paths)),
+        Value::Dictionary(map) => map.values().for_each(|v| collect_form_files(v, paths)),
+        _ => (),
+    }
+}
+
+async fn remove_files(paths: impl Iterator<Item = impl AsRef<Path>>) {
+    for path in paths {
+        if let Err(error) = tokio::fs::remove_file(path.as_ref()).await {
+            log::warn!("cannot remove uploaded file `{}`: {}", path.as_ref().display(), error);
+        }
+    }
+}
+
+async fn read_multipart_form<S, O, E>(content_type: &str, body: S, options: &MultipartOptions, written: &mut Vec<PathBuf>) -> Result<Value> where
+    S: Stream<Item = std::result::Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+{
+    let boundary = multer::parse_boundary(content_type).map_err(|_| Error::invalid_request_message("invalid multipart content type"))?;
+    let mut size_limit = SizeLimit::new();
+    if let Some(max_request_size) = options.max_request_size {
+        size_limit = size_limit.whole_stream(max_request_size);
+    }
+    let mut multipart = Multipart::with_constraints(body, boundary, Constraints::new().size_limit(size_limit));
+    let mut form = IndexMap::new();
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let Some(name) = field.name().map(|n| n.to_owned()) else {
+            continue
+        };
+        let value = if let Some(filename) = field.file_name().map(|n| n.to_owned()) {
+            let content_type = field.content_type().map(|m| m.to_string());
+            let filename_ext = Path::new(&filename).extension().map(|e| e.to_string_lossy().to_string());
+            tokio::fs::create_dir_all(&options.temp_dir).await.map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            let filepath = options.temp_dir.join(match &filename_ext {
+                Some(ext) => format!("{}.{}", Uuid::new_v4(), ext),
+                None => Uuid::new_v4().to_string(),
+            });
+            let mut file = tokio::fs::File::create(&filepath).await.map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            written.push(filepath.clone());
+            let mut size = 0u64;
+            while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+                size += chunk.len() as u64;
+                if options.max_file_size.is_some_and(|max| size > max) {
+                    return Err(payload_too_large(Error::invalid_request_pathed(path![name.as_str()], "file is too large")));
+                }
+                file.write_all(&chunk).await.map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            }
+            file.flush().await.map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            Value::File(File {
+                filepath: filepath.to_string_lossy().to_string(),
+                content_type,
+                filename,
+                filename_ext,
+            })
+        } else {
+            Value::String(field.text().await.map_err(multipart_error)?)
+        };
+        insert_form_value(&mut form, &name, value)?;
+    }
+    Ok(Value::Dictionary(form))
+}
+
+fn multipart_error(error: multer::Error) -> Error {
+    match error {
+        multer::Error::StreamSizeExceeded { .. } => Error::new_with_code("request body is too large", 413),
+        multer::Error::FieldSizeExceeded { field_name, .. } => match field_name {
+            Some(field_name) => payload_too_large(Error::invalid_request_pathed(path![field_name.as_str()], "field is too large")),
+            None => Error::new_with_code("field is too large", 413),
+        },
+        error => Error::invalid_request_message(error.to_string()),
+    }
+}
+
+fn payload_too_large(mut error: Error) -> Error {
+    error.code = 413;
+    error
+}
+
+/// Validate a form from `parse_multipart_form`. When the form is rejected, its
+/// uploaded files are removed.
+pub fn validate_and_transform_form_input_for_handler(handler: &Handler, form: Value, main_namespace: &Namespace) -> Result<Value> {
+    let mut paths = vec![];
+    collect_form_files(&form, &mut paths);
+    let paths: Vec<String> = paths.into_iter().map(|p| p.to_owned()).collect();
+    let result = form_to_teon_with_type(form, &path![], &handler.input_type, main_namespace);
+    if result.is_err() {
+        for path in paths {
+            if let Err(error) = std::fs::remove_file(&path) {
+                log::warn!("cannot remove uploaded file `{}`: {}", path, error);
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+    use crate::value::Value;
+    use super::{parse_multipart_form, remove_form_files, MultipartOptions};
+
+    const CONTENT_TYPE: &str = "multipart/form-data; boundary=X";
+
+    fn body(parts: &[(&str, Option<&str>, &str)]) -> impl futures_util::Stream<Item = Result<Bytes, std::io::Error>> + Send + 'static {
+        let mut body = String::new();
+        for (name, filename, content) in parts {
+            body += "--X\r\n";
+            match filename {
+                Some(filename) => body += &format!("Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: text/plain\r\n\r\n", name, filename),
+                None => body += &format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n", name),
+            }
+            body += content;
+            body += "\r\n";
+        }
+        body += "--X--\r\n";
+        stream::iter(vec![Ok(Bytes::from(body))])
+    }
+
+    fn options() -> MultipartOptions {
+        MultipartOptions {
+            temp_dir: std::env::temp_dir().join(format!("teo-form-{}", uuid::Uuid::new_v4())),
+            max_file_size: Some(16),
+            max_request_size: None,
+        }
+    }
+
+    fn file_count(options: &MultipartOptions) -> usize {
+        std::fs::read_dir(&options.temp_dir).map_or(0, |entries| entries.count())
+    }
+
+    #[tokio::test]
+    async fn parses_text_and_file_parts() {
+        let options = options();
+        let form = parse_multipart_form(CONTENT_TYPE, body(&[("name", None, "teo"), ("avatar", Some("a.txt"), "hello")]), &options).await.unwrap();
+        assert_eq!(form.get("name"), Some(&Value::String("teo".to_owned())));
+        let Some(Value::File(file)) = form.get("avatar") else { panic!("expect file") };
+        assert_eq!(std::fs::read_to_string(&file.filepath).unwrap(), "hello");
+        assert_eq!(file.filename_ext.as_deref(), Some("txt"));
+        remove_form_files(&form).await;
+        assert_eq!(file_count(&options), 0);
+    }
+
+    #[tokio::test]
+    async fn removes_every_written_file_on_error() {
+        let options = options();
+        let parts = [("first", Some("a.txt"), "small"), ("second", Some("b.txt"), "this content is way too large")];
+        let error = parse_multipart_form(CONTENT_TYPE, body(&parts), &options).await.unwrap_err();
+        assert_eq!(error.code, 413);
+        assert_eq!(file_count(&options), 0);
+    }
+
+    #[test]
+    fn default_limits_are_finite() {
+        let options = MultipartOptions::default();
+        assert!(options.max_file_size.is_some());
+        assert!(options.max_request_size.is_some_and(|max| max >= options.max_file_size.unwrap()));
+    }
+}
@@ -1,5 +1,13 @@
 pub mod builtin;
 pub mod custom;
+pub mod form;
+pub mod query;
+pub mod path_args;
+pub mod binary;
 
 pub use builtin::validate_and_transform_json_input_for_builtin_action;
-pub use custom::validate_and_transform_json_input_for_handler;
\ No newline at end of file
+pub use custom::validate_and_transform_json_input_for_handler;
+pub use form::{parse_multipart_form, remove_form_files, validate_and_transform_form_input_for_handler, MultipartOptions};
+pub use query::{parse_query_string, validate_and_transform_query_input_for_handler};
+pub use path_args::{PathArgs, validate_and_transform_path_args_for_handler};
+pub use binary::validate_and_transform_binary_input_for_handler;
@@ -0,0 +1,48 @@
+use indexmap::IndexMap;
+use key_path::path;
+use teo_result::{Error, Result};
+use crate::coder::form_to_teon::form_to_teon_with_shape;
+use crate::handler::Handler;
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::namespace::Namespace;
+use crate::request::Ctx;
+use crate::request::ctx::extract::ExtractFromRequestCtx;
+use crate::value::Value;
+
+/// The path captures of the matched url, converted against the handler's url
+/// args interface when it declares one.
+#[derive(Debug, Clone)]
+pub struct PathArgs(pub Value);
+
+impl ExtractFromRequestCtx for PathArgs {
+    fn extract(ctx: &Ctx) -> Self {
+        PathArgs(ctx.path_args())
+    }
+}
+
+pub fn validate_and_transform_path_args_for_handler(handler: &Handler, captures: &IndexMap<String, String>, main_namespace: &Namespace) -> Result<Value> {
+    validate_and_transform_path_args(captures, handler.custom_url_args_path().as_ref(), main_namespace)
+}
+
+fn validate_and_transform_path_args(captures: &IndexMap<String, String>, interface_path: Option<&Vec<String>>, main_namespace: &Namespace) -> Result<Value> {
+    let args = Value::Dictionary(captures.iter().map(|(k, v)| (k.clone(), Value::String(v.clone()))).collect());
+    let Some(interface_path) = interface_path else {
+        return Ok(args);
+    };
+    let Some(interface) = main_namespace.interface_at_path(&interface_path.iter().map(AsRef::as_ref).collect()) else {
+        return Err(Error::internal_server_error_message(format!("url args interface `{}` is not found", interface_path.join("."))));
+    };
+    form_to_teon_with_shape(args, &path![], &interface.shape_from_generics(&vec![]), main_namespace)
+}
+
+/// Validates the path captures before the handler runs, so that a mismatch
+/// is rejected with a request error instead of reaching the handler.
+pub(crate) fn path_args_middleware(interface_path: Vec<String>) -> &'static dyn Middleware {
+    let interface_path: &'static Vec<String> = Box::leak(Box::new(interface_path));
+    Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+        let args = validate_and_transform_path_args(ctx.handler_match().captures(), Some(interface_path), ctx.namespace())?;
+        ctx.set_path_args(args);
+        next.call(ctx).await
+    }))
+}
@@ -0,0 +1,90 @@
+use indexmap::IndexMap;
+use key_path::path;
+use teo_result::Result;
+use crate::coder::form_to_teon::{form_to_teon_with_type, insert_form_value};
+use crate::handler::Handler;
+use crate::namespace::Namespace;
+use crate::utils::percent_decode;
+use crate::value::Value;
+
+/// Decode a query string into a dictionary of strings. Nested keys like
+/// `a[b]=1` and repeated keys are supported.
+pub fn parse_query_string(query_string: &str) -> Result<Value> {
+    let mut query = IndexMap::new();
+    let query_string = query_string.strip_prefix('?').unwrap_or(query_string);
+    for pair in query_string.split('&') {
+        if pair.is_empty() {
+            continue
+        }
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = percent_decode(key, true);
+        if key.is_empty() {
+            continue
+        }
+        insert_form_value(&mut query, &key, Value::String(percent_decode(value, true)))?;
+    }
+    Ok(Value::Dictionary(query))
+}
+
+/// The input of handlers without a body, which is decoded from the query
+/// string and validated against the handler's input type.
+pub fn validate_and_transform_query_input_for_handler(handler: &Handler, query_string: &str, main_namespace: &Namespace) -> Result<Value> {
+    form_to_teon_with_type(parse_query_string(query_string)?, &path![], &handler.input_type, main_namespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use crate::coder::form_to_teon::MAX_FORM_KEY_SEGMENTS;
+    use crate::value::Value;
+    use super::parse_query_string;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_owned())
+    }
+
+    fn dictionary<const N: usize>(entries: [(&str, Value); N]) -> Value {
+        Value::Dictionary(entries.into_iter().map(|(k, v)| (k.to_owned(), v)).collect::<IndexMap<_, _>>())
+    }
+
+    #[test]
+    fn decodes_plain_keys() {
+        assert_eq!(parse_query_string("?a=1&b=x%20y&c").unwrap(), dictionary([("a", string("1")), ("b", string("x y")), ("c", string(""))]));
+        assert_eq!(parse_query_string("").unwrap(), dictionary([]));
+    }
+
+    #[test]
+    fn decodes_nested_keys() {
+        assert_eq!(parse_query_string("a[b][c]=1&a[d]=2").unwrap(), dictionary([
+            ("a", dictionary([("b", dictionary([("c", string("1"))])), ("d", string("2"))])),
+        ]));
+        assert_eq!(parse_query_string("a%5Bb%5D=1").unwrap(), dictionary([("a", dictionary([("b", string("1"))]))]));
+    }
+
+    #[test]
+    fn empty_brackets_append_to_an_array() {
+        assert_eq!(parse_query_string("a[]=1&a[]=2").unwrap(), dictionary([("a", Value::Array(vec![string("1"), string("2")]))]));
+        assert_eq!(parse_query_string("a[]=").unwrap(), dictionary([("a", Value::Array(vec![string("")]))]));
+        assert_eq!(parse_query_string("a[][b]=1").unwrap(), dictionary([("a", Value::Array(vec![dictionary([("b", string("1"))])]))]));
+    }
+
+    #[test]
+    fn repeated_keys_are_collected() {
+        assert_eq!(parse_query_string("a=1&a=2&a=3").unwrap(), dictionary([("a", Value::Array(vec![string("1"), string("2"), string("3")]))]));
+        assert_eq!(parse_query_string("a[b]=1&a[b]=2").unwrap(), dictionary([("a", dictionary([("b", Value::Array(vec![string("1"), string("2")]))]))]));
+    }
+
+    #[test]
+    fn nested_key_replaces_a_plain_value() {
+        assert_eq!(parse_query_string("a=1&a[b]=2").unwrap(), dictionary([("a", dictionary([("b", string("2"))]))]));
+    }
+
+    #[test]
+    fn deeply_nested_keys_are_rejected() {
+        let key = format!("a{}", "[x]".repeat(MAX_FORM_KEY_SEGMENTS - 1));
+        assert!(parse_query_string(&format!("{}=1", key)).is_ok());
+        let error = parse_query_string(&format!("{}[x]=1", key)).unwrap_err();
+        assert_eq!(error.code, 400);
+        assert!(parse_query_string(&format!("a{}=1", "[x]".repeat(100_000))).is_err());
+    }
+}
@@ -17,26 +17,7 @@ impl Map {
     }
 
     pub fn add_record(&mut self, namespace_path: &Vec<&str>, group_name: Option<&str>, action_name: &str, method: Method, custom_url: Option<&str>, ignore_prefix: bool) {
-        let url = if ignore_prefix {
-            if custom_url.unwrap().starts_with("/") {
-                custom_url.unwrap().to_owned()
-            } else {
-                "/".to_owned() + custom_url.unwrap()
-            }
-        } else {
-            "/".to_owned() + &namespace_path.join(".") + &if let Some(group_name) = group_name {
-                "/".to_owned() + group_name
-            } else { "".to_owned() } + &if let Some(custom_url) = custom_url {
-                if custom_url.starts_with("/") {
-                    custom_url.to_owned()
-                } else {
-                    "/".to_owned() + custom_url
-                }
-            } else {
-                action_name.to_owned()
-            }
-        };
-        let url = url.replace("//", "/");
+        let url = handler_url(namespace_path, group_name, action_name, custom_url, ignore_prefix);
         let mut result: Vec<String> = namespace_path.iter().map(|i| i.to_string()).collect();
         if let Some(group_name) = group_name {
             result.push(group_name.to_owned());
@@ -44,6 +25,11 @@ impl Map {
         self.records.insert((method, url), (result, action_name.to_owned()));
     }
 
+    /// Every route as its method, url, handler group path and handler name.
+    pub fn records(&self) -> impl Iterator<Item = (Method, &str, &Vec<String>, &str)> {
+        self.records.iter().map(|((method, url), (path, name))| (*method, url.as_str(), path, name.as_str()))
+    }
+
     pub fn r#match(&self, method: Method, url: &str) -> Option<HandlerMatch> {
         for record in &self.records {
             if let Some(result) = self.try_match(method, url, record) {
@@ -145,4 +131,27 @@ impl Map {
             path
         }
     }
-}
\ No newline at end of file
+}
+
+pub(crate) fn handler_url(namespace_path: &Vec<&str>, group_name: Option<&str>, action_name: &str, custom_url: Option<&str>, ignore_prefix: bool) -> String {
+    let url = if ignore_prefix {
+        if custom_url.unwrap().starts_with("/") {
+            custom_url.unwrap().to_owned()
+        } else {
+            "/".to_owned() + custom_url.unwrap()
+        }
+    } else {
+        "/".to_owned() + &namespace_path.join(".") + &if let Some(group_name) = group_name {
+            "/".to_owned() + group_name
+        } else { "".to_owned() } + &if let Some(custom_url) = custom_url {
+            if custom_url.starts_with("/") {
+                custom_url.to_owned()
+            } else {
+                "/".to_owned() + custom_url
+            }
+        } else {
+            action_name.to_owned()
+        }
+    };
+    url.replace("//", "/")
+}
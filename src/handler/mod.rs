@@ -2,6 +2,7 @@ pub mod group;
 pub mod handler;
 pub mod decorator;
 pub mod default;
+pub mod batch;
 pub mod r#match;
 pub mod map;
 pub mod action;
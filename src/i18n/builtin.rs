@@ -0,0 +1,544 @@
+/// Translations of the runtime's built-in messages, in the order de, fr, es,
+/// hi, he, ja, ko, zhCn, zhTw. English messages are their own ids, `{}`
+/// stands for the arguments of formatted messages.
+pub(super) static BUILTIN_MESSAGES: &[(&str, [&str; 9])] = &[
+    ("unexpected input", [
+        "unerwartete Eingabe",
+        "entrée inattendue",
+        "entrada inesperada",
+        "अप्रत्याशित इनपुट",
+        "קלט לא צפוי",
+        "予期しない入力です",
+        "예상하지 못한 입력입니다",
+        "意外的输入",
+        "非預期的輸入",
+    ]),
+    ("missing required input", [
+        "erforderliche Eingabe fehlt",
+        "entrée requise manquante",
+        "falta una entrada obligatoria",
+        "आवश्यक इनपुट गायब है",
+        "חסר קלט נדרש",
+        "必須の入力がありません",
+        "필수 입력이 없습니다",
+        "缺少必需的输入",
+        "缺少必要的輸入",
+    ]),
+    ("value is invalid", [
+        "Wert ist ungültig",
+        "la valeur est invalide",
+        "el valor no es válido",
+        "मान अमान्य है",
+        "הערך אינו תקין",
+        "値が無効です",
+        "값이 올바르지 않습니다",
+        "值无效",
+        "值無效",
+    ]),
+    ("not found", [
+        "nicht gefunden",
+        "introuvable",
+        "no encontrado",
+        "नहीं मिला",
+        "לא נמצא",
+        "見つかりません",
+        "찾을 수 없습니다",
+        "未找到",
+        "找不到",
+    ]),
+    ("input is not email", [
+        "Eingabe ist keine E-Mail-Adresse",
+        "l'entrée n'est pas une adresse e-mail",
+        "la entrada no es un correo electrónico",
+        "इनपुट ईमेल नहीं है",
+        "הקלט אינו כתובת דוא\"ל",
+        "入力はメールアドレスではありません",
+        "입력값이 이메일이 아닙니다",
+        "输入不是电子邮件地址",
+        "輸入不是電子郵件地址",
+    ]),
+    ("input is not hex color", [
+        "Eingabe ist keine Hex-Farbe",
+        "l'entrée n'est pas une couleur hexadécimale",
+        "la entrada no es un color hexadecimal",
+        "इनपुट हेक्स रंग नहीं है",
+        "הקלט אינו צבע הקסדצימלי",
+        "入力は16進数のカラーではありません",
+        "입력값이 16진수 색상이 아닙니다",
+        "输入不是十六进制颜色",
+        "輸入不是十六進位顏色",
+    ]),
+    ("input is not secure password", [
+        "Eingabe ist kein sicheres Passwort",
+        "l'entrée n'est pas un mot de passe sécurisé",
+        "la entrada no es una contraseña segura",
+        "इनपुट सुरक्षित पासवर्ड नहीं है",
+        "הקלט אינו סיסמה מאובטחת",
+        "入力は安全なパスワードではありません",
+        "입력값이 안전한 비밀번호가 아닙니다",
+        "输入不是安全的密码",
+        "輸入不是安全的密碼",
+    ]),
+    ("input is not numeric", [
+        "Eingabe ist nicht numerisch",
+        "l'entrée n'est pas numérique",
+        "la entrada no es numérica",
+        "इनपुट संख्यात्मक नहीं है",
+        "הקלט אינו מספרי",
+        "入力は数字ではありません",
+        "입력값이 숫자가 아닙니다",
+        "输入不是数字",
+        "輸入不是數字",
+    ]),
+    ("input is not alphanumeric", [
+        "Eingabe ist nicht alphanumerisch",
+        "l'entrée n'est pas alphanumérique",
+        "la entrada no es alfanumérica",
+        "इनपुट अक्षरांकीय नहीं है",
+        "הקלט אינו אלפאנומרי",
+        "入力は英数字ではありません",
+        "입력값이 영숫자가 아닙니다",
+        "输入不是字母或数字",
+        "輸入不是字母或數字",
+    ]),
+    ("input is not alphabetic", [
+        "Eingabe ist nicht alphabetisch",
+        "l'entrée n'est pas alphabétique",
+        "la entrada no es alfabética",
+        "इनपुट वर्णमाला नहीं है",
+        "הקלט אינו אלפביתי",
+        "入力は英字ではありません",
+        "입력값이 알파벳이 아닙니다",
+        "输入不是字母",
+        "輸入不是字母",
+    ]),
+    ("expect string", [
+        "Zeichenkette erwartet",
+        "chaîne attendue",
+        "se esperaba una cadena",
+        "स्ट्रिंग अपेक्षित है",
+        "צפויה מחרוזת",
+        "文字列が必要です",
+        "문자열이 필요합니다",
+        "应为字符串",
+        "應為字串",
+    ]),
+    ("expect int", [
+        "Ganzzahl erwartet",
+        "entier attendu",
+        "se esperaba un entero",
+        "पूर्णांक अपेक्षित है",
+        "צפוי מספר שלם",
+        "整数が必要です",
+        "정수가 필요합니다",
+        "应为整数",
+        "應為整數",
+    ]),
+    ("expect bool", [
+        "Wahrheitswert erwartet",
+        "booléen attendu",
+        "se esperaba un booleano",
+        "बूलियन अपेक्षित है",
+        "צפוי ערך בוליאני",
+        "真偽値が必要です",
+        "불리언 값이 필요합니다",
+        "应为布尔值",
+        "應為布林值",
+    ]),
+    ("cannot disconnect previous relation", [
+        "vorherige Relation kann nicht getrennt werden",
+        "impossible de dissocier la relation précédente",
+        "no se puede desvincular la relación anterior",
+        "पिछला संबंध अलग नहीं किया जा सकता",
+        "לא ניתן לנתק את הקשר הקודם",
+        "以前のリレーションを切断できません",
+        "이전 관계를 끊을 수 없습니다",
+        "无法断开之前的关联",
+        "無法斷開先前的關聯",
+    ]),
+    ("object is not saved thus can't be deleted", [
+        "Objekt ist nicht gespeichert und kann daher nicht gelöscht werden",
+        "l'objet n'est pas enregistré et ne peut donc pas être supprimé",
+        "el objeto no está guardado, por lo que no se puede eliminar",
+        "ऑब्जेक्ट सहेजा नहीं गया है इसलिए हटाया नहीं जा सकता",
+        "האובייקט לא נשמר ולכן לא ניתן למחוק אותו",
+        "オブジェクトが保存されていないため削除できません",
+        "객체가 저장되지 않아 삭제할 수 없습니다",
+        "对象未保存，因此无法删除",
+        "物件未儲存，因此無法刪除",
+    ]),
+    ("missing required input {}", [
+        "erforderliche Eingabe {} fehlt",
+        "entrée requise {} manquante",
+        "falta la entrada obligatoria {}",
+        "आवश्यक इनपुट {} गायब है",
+        "חסר קלט נדרש {}",
+        "必須の入力 {} がありません",
+        "필수 입력 {}이(가) 없습니다",
+        "缺少必需的输入 {}",
+        "缺少必要的輸入 {}",
+    ]),
+    ("key '{}' is invalid on model {}", [
+        "Schlüssel '{}' ist für Modell {} ungültig",
+        "la clé '{}' est invalide pour le modèle {}",
+        "la clave '{}' no es válida en el modelo {}",
+        "कुंजी '{}' मॉडल {} पर अमान्य है",
+        "המפתח '{}' אינו תקין במודל {}",
+        "キー '{}' はモデル {} では無効です",
+        "키 '{}'는 모델 {}에서 올바르지 않습니다",
+        "键 '{}' 在模型 {} 上无效",
+        "鍵 '{}' 在模型 {} 上無效",
+    ]),
+    ("deletion denied {}", [
+        "Löschen verweigert: {}",
+        "suppression refusée : {}",
+        "eliminación denegada: {}",
+        "हटाना अस्वीकृत: {}",
+        "המחיקה נדחתה: {}",
+        "削除が拒否されました: {}",
+        "삭제가 거부되었습니다: {}",
+        "拒绝删除：{}",
+        "拒絕刪除：{}",
+    ]),
+    ("updation denied {}", [
+        "Aktualisierung verweigert: {}",
+        "mise à jour refusée : {}",
+        "actualización denegada: {}",
+        "अद्यतन अस्वीकृत: {}",
+        "העדכון נדחה: {}",
+        "更新が拒否されました: {}",
+        "업데이트가 거부되었습니다: {}",
+        "拒绝更新：{}",
+        "拒絕更新：{}",
+    ]),
+    ("unique value duplicated: {}", [
+        "eindeutiger Wert doppelt vorhanden: {}",
+        "valeur unique en double : {}",
+        "valor único duplicado: {}",
+        "अद्वितीय मान दोहराया गया: {}",
+        "ערך ייחודי כפול: {}",
+        "一意の値が重複しています: {}",
+        "고유 값이 중복되었습니다: {}",
+        "唯一值重复：{}",
+        "唯一值重複：{}",
+    ]),
+    ("input is not prefix of \"{}\"", [
+        "Eingabe ist kein Präfix von \"{}\"",
+        "l'entrée n'est pas un préfixe de \"{}\"",
+        "la entrada no es un prefijo de \"{}\"",
+        "इनपुट \"{}\" का उपसर्ग नहीं है",
+        "הקלט אינו תחילית של \"{}\"",
+        "入力は \"{}\" の接頭辞ではありません",
+        "입력값이 \"{}\"의 접두사가 아닙니다",
+        "输入不是 \"{}\" 的前缀",
+        "輸入不是 \"{}\" 的前綴",
+    ]),
+    ("input is not suffix of \"{}\"", [
+        "Eingabe ist kein Suffix von \"{}\"",
+        "l'entrée n'est pas un suffixe de \"{}\"",
+        "la entrada no es un sufijo de \"{}\"",
+        "इनपुट \"{}\" का प्रत्यय नहीं है",
+        "הקלט אינו סיפא של \"{}\"",
+        "入力は \"{}\" の接尾辞ではありません",
+        "입력값이 \"{}\"의 접미사가 아닙니다",
+        "输入不是 \"{}\" 的后缀",
+        "輸入不是 \"{}\" 的後綴",
+    ]),
+    ("input doesn't have prefix \"{}\"", [
+        "Eingabe hat nicht das Präfix \"{}\"",
+        "l'entrée n'a pas le préfixe \"{}\"",
+        "la entrada no tiene el prefijo \"{}\"",
+        "इनपुट में उपसर्ग \"{}\" नहीं है",
+        "לקלט אין את התחילית \"{}\"",
+        "入力に接頭辞 \"{}\" がありません",
+        "입력값에 접두사 \"{}\"가 없습니다",
+        "输入没有前缀 \"{}\"",
+        "輸入沒有前綴 \"{}\"",
+    ]),
+    ("input doesn't have suffix \"{}\"", [
+        "Eingabe hat nicht das Suffix \"{}\"",
+        "l'entrée n'a pas le suffixe \"{}\"",
+        "la entrada no tiene el sufijo \"{}\"",
+        "इनपुट में प्रत्यय \"{}\" नहीं है",
+        "לקלט אין את הסיפא \"{}\"",
+        "入力に接尾辞 \"{}\" がありません",
+        "입력값에 접미사 \"{}\"가 없습니다",
+        "输入没有后缀 \"{}\"",
+        "輸入沒有後綴 \"{}\"",
+    ]),
+    ("input doesn't match regex", [
+        "Eingabe entspricht nicht dem regulären Ausdruck",
+        "l'entrée ne correspond pas à l'expression régulière",
+        "la entrada no coincide con la expresión regular",
+        "इनपुट रेगुलर एक्सप्रेशन से मेल नहीं खाता",
+        "הקלט אינו תואם לביטוי הרגולרי",
+        "入力が正規表現に一致しません",
+        "입력값이 정규식과 일치하지 않습니다",
+        "输入与正则表达式不匹配",
+        "輸入與正規表示式不符",
+    ]),
+    ("input is not present", [
+        "Eingabe ist nicht vorhanden",
+        "l'entrée est absente",
+        "la entrada no está presente",
+        "इनपुट मौजूद नहीं है",
+        "הקלט אינו קיים",
+        "入力がありません",
+        "입력값이 없습니다",
+        "输入不存在",
+        "輸入不存在",
+    ]),
+    ("input is not true", [
+        "Eingabe ist nicht wahr",
+        "l'entrée n'est pas vraie",
+        "la entrada no es verdadera",
+        "इनपुट सत्य नहीं है",
+        "הקלט אינו אמת",
+        "入力が true ではありません",
+        "입력값이 true가 아닙니다",
+        "输入不为真",
+        "輸入不為真",
+    ]),
+    ("unexpected key", [
+        "unerwarteter Schlüssel",
+        "clé inattendue",
+        "clave inesperada",
+        "अप्रत्याशित कुंजी",
+        "מפתח לא צפוי",
+        "予期しないキーです",
+        "예상하지 못한 키입니다",
+        "意外的键",
+        "非預期的鍵",
+    ]),
+    ("unexpected type", [
+        "unerwarteter Typ",
+        "type inattendu",
+        "tipo inesperado",
+        "अप्रत्याशित प्रकार",
+        "סוג לא צפוי",
+        "予期しない型です",
+        "예상하지 못한 타입입니다",
+        "意外的类型",
+        "非預期的型別",
+    ]),
+    ("unexpected value", [
+        "unerwarteter Wert",
+        "valeur inattendue",
+        "valor inesperado",
+        "अप्रत्याशित मान",
+        "ערך לא צפוי",
+        "予期しない値です",
+        "예상하지 못한 값입니다",
+        "意外的值",
+        "非預期的值",
+    ]),
+    ("expect null", [
+        "null erwartet",
+        "null attendu",
+        "se esperaba null",
+        "null अपेक्षित है",
+        "צפוי null",
+        "null が必要です",
+        "null이 필요합니다",
+        "应为 null",
+        "應為 null",
+    ]),
+    ("expect int 64", [
+        "64-Bit-Ganzzahl erwartet",
+        "entier 64 bits attendu",
+        "se esperaba un entero de 64 bits",
+        "64-बिट पूर्णांक अपेक्षित है",
+        "צפוי מספר שלם של 64 סיביות",
+        "64ビット整数が必要です",
+        "64비트 정수가 필요합니다",
+        "应为 64 位整数",
+        "應為 64 位元整數",
+    ]),
+    ("expect float 32", [
+        "32-Bit-Gleitkommazahl erwartet",
+        "nombre à virgule flottante 32 bits attendu",
+        "se esperaba un número de coma flotante de 32 bits",
+        "32-बिट फ़्लोट अपेक्षित है",
+        "צפוי מספר נקודה צפה של 32 סיביות",
+        "32ビット浮動小数点数が必要です",
+        "32비트 부동소수점 수가 필요합니다",
+        "应为 32 位浮点数",
+        "應為 32 位元浮點數",
+    ]),
+    ("expect float", [
+        "Gleitkommazahl erwartet",
+        "nombre à virgule flottante attendu",
+        "se esperaba un número de coma flotante",
+        "फ़्लोट अपेक्षित है",
+        "צפוי מספר נקודה צפה",
+        "浮動小数点数が必要です",
+        "부동소수점 수가 필요합니다",
+        "应为浮点数",
+        "應為浮點數",
+    ]),
+    ("expect string or number which represents decimal", [
+        "Zeichenkette oder Zahl erwartet, die eine Dezimalzahl darstellt",
+        "chaîne ou nombre représentant un décimal attendu",
+        "se esperaba una cadena o un número que represente un decimal",
+        "दशमलव दर्शाने वाली स्ट्रिंग या संख्या अपेक्षित है",
+        "צפויה מחרוזת או מספר המייצגים מספר עשרוני",
+        "10進数を表す文字列または数値が必要です",
+        "10진수를 나타내는 문자열 또는 숫자가 필요합니다",
+        "应为表示十进制数的字符串或数字",
+        "應為表示十進位數的字串或數字",
+    ]),
+    ("string is not valid decimal", [
+        "Zeichenkette ist keine gültige Dezimalzahl",
+        "la chaîne n'est pas un décimal valide",
+        "la cadena no es un decimal válido",
+        "स्ट्रिंग मान्य दशमलव नहीं है",
+        "המחרוזת אינה מספר עשרוני תקין",
+        "文字列は有効な10進数ではありません",
+        "문자열이 올바른 10진수가 아닙니다",
+        "字符串不是有效的十进制数",
+        "字串不是有效的十進位數",
+    ]),
+    ("number is not valid decimal", [
+        "Zahl ist keine gültige Dezimalzahl",
+        "le nombre n'est pas un décimal valide",
+        "el número no es un decimal válido",
+        "संख्या मान्य दशमलव नहीं है",
+        "המספר אינו מספר עשרוני תקין",
+        "数値は有効な10進数ではありません",
+        "숫자가 올바른 10진수가 아닙니다",
+        "数字不是有效的十进制数",
+        "數字不是有效的十進位數",
+    ]),
+    ("expect string represents object id", [
+        "Zeichenkette erwartet, die eine Objekt-ID darstellt",
+        "chaîne représentant un identifiant d'objet attendue",
+        "se esperaba una cadena que represente un id de objeto",
+        "ऑब्जेक्ट आईडी दर्शाने वाली स्ट्रिंग अपेक्षित है",
+        "צפויה מחרוזת המייצגת מזהה אובייקט",
+        "オブジェクトIDを表す文字列が必要です",
+        "객체 ID를 나타내는 문자열이 필요합니다",
+        "应为表示对象 ID 的字符串",
+        "應為表示物件 ID 的字串",
+    ]),
+    ("string is not valid object id", [
+        "Zeichenkette ist keine gültige Objekt-ID",
+        "la chaîne n'est pas un identifiant d'objet valide",
+        "la cadena no es un id de objeto válido",
+        "स्ट्रिंग मान्य ऑब्जेक्ट आईडी नहीं है",
+        "המחרוזת אינה מזהה אובייקט תקין",
+        "文字列は有効なオブジェクトIDではありません",
+        "문자열이 올바른 객체 ID가 아닙니다",
+        "字符串不是有效的对象 ID",
+        "字串不是有效的物件 ID",
+    ]),
+    ("expect string represents date", [
+        "Zeichenkette erwartet, die ein Datum darstellt",
+        "chaîne représentant une date attendue",
+        "se esperaba una cadena que represente una fecha",
+        "तिथि दर्शाने वाली स्ट्रिंग अपेक्षित है",
+        "צפויה מחרוזת המייצגת תאריך",
+        "日付を表す文字列が必要です",
+        "날짜를 나타내는 문자열이 필요합니다",
+        "应为表示日期的字符串",
+        "應為表示日期的字串",
+    ]),
+    ("string is not valid date", [
+        "Zeichenkette ist kein gültiges Datum",
+        "la chaîne n'est pas une date valide",
+        "la cadena no es una fecha válida",
+        "स्ट्रिंग मान्य तिथि नहीं है",
+        "המחרוזת אינה תאריך תקין",
+        "文字列は有効な日付ではありません",
+        "문자열이 올바른 날짜가 아닙니다",
+        "字符串不是有效的日期",
+        "字串不是有效的日期",
+    ]),
+    ("expect string represents datetime", [
+        "Zeichenkette erwartet, die einen Zeitpunkt darstellt",
+        "chaîne représentant une date et heure attendue",
+        "se esperaba una cadena que represente una fecha y hora",
+        "दिनांक-समय दर्शाने वाली स्ट्रिंग अपेक्षित है",
+        "צפויה מחרוזת המייצגת תאריך ושעה",
+        "日時を表す文字列が必要です",
+        "날짜와 시간을 나타내는 문자열이 필요합니다",
+        "应为表示日期时间的字符串",
+        "應為表示日期時間的字串",
+    ]),
+    ("string is not valid datetime", [
+        "Zeichenkette ist kein gültiger Zeitpunkt",
+        "la chaîne n'est pas une date et heure valide",
+        "la cadena no es una fecha y hora válida",
+        "स्ट्रिंग मान्य दिनांक-समय नहीं है",
+        "המחרוזת אינה תאריך ושעה תקינים",
+        "文字列は有効な日時ではありません",
+        "문자열이 올바른 날짜와 시간이 아닙니다",
+        "字符串不是有效的日期时间",
+        "字串不是有效的日期時間",
+    ]),
+    ("expect array", [
+        "Array erwartet",
+        "tableau attendu",
+        "se esperaba un arreglo",
+        "ऐरे अपेक्षित है",
+        "צפוי מערך",
+        "配列が必要です",
+        "배열이 필요합니다",
+        "应为数组",
+        "應為陣列",
+    ]),
+    ("expect dictionary", [
+        "Wörterbuch erwartet",
+        "dictionnaire attendu",
+        "se esperaba un diccionario",
+        "डिक्शनरी अपेक्षित है",
+        "צפוי מילון",
+        "辞書が必要です",
+        "딕셔너리가 필요합니다",
+        "应为字典",
+        "應為字典",
+    ]),
+    ("expect enum member", [
+        "Enum-Mitglied erwartet",
+        "membre d'énumération attendu",
+        "se esperaba un miembro de enumeración",
+        "एनम सदस्य अपेक्षित है",
+        "צפוי איבר של enum",
+        "列挙型のメンバーが必要です",
+        "열거형 멤버가 필요합니다",
+        "应为枚举成员",
+        "應為列舉成員",
+    ]),
+    ("expect string represents enum member", [
+        "Zeichenkette erwartet, die ein Enum-Mitglied darstellt",
+        "chaîne représentant un membre d'énumération attendue",
+        "se esperaba una cadena que represente un miembro de enumeración",
+        "एनम सदस्य दर्शाने वाली स्ट्रिंग अपेक्षित है",
+        "צפויה מחרוזת המייצגת איבר של enum",
+        "列挙型のメンバーを表す文字列が必要です",
+        "열거형 멤버를 나타내는 문자열이 필요합니다",
+        "应为表示枚举成员的字符串",
+        "應為表示列舉成員的字串",
+    ]),
+    ("expect string enum variant", [
+        "Zeichenketten-Enum-Variante erwartet",
+        "variante d'énumération sous forme de chaîne attendue",
+        "se esperaba una variante de enumeración de tipo cadena",
+        "स्ट्रिंग एनम वेरिएंट अपेक्षित है",
+        "צפוי ערך enum מסוג מחרוזת",
+        "文字列の列挙型バリアントが必要です",
+        "문자열 열거형 변형이 필요합니다",
+        "应为字符串枚举变体",
+        "應為字串列舉變體",
+    ]),
+    ("expect value", [
+        "Wert erwartet",
+        "valeur attendue",
+        "se esperaba un valor",
+        "मान अपेक्षित है",
+        "צפוי ערך",
+        "値が必要です",
+        "값이 필요합니다",
+        "应为值",
+        "應為值",
+    ]),
+];
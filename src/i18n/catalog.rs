@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use teo_result::Error;
+use crate::admin::language::Language;
+use crate::i18n::builtin::BUILTIN_MESSAGES;
+
+const BUILTIN_LANGUAGES: [Language; 9] = [
+    Language::De,
+    Language::Fr,
+    Language::Es,
+    Language::Hi,
+    Language::He,
+    Language::Ja,
+    Language::Ko,
+    Language::ZhCn,
+    Language::ZhTw,
+];
+
+/// Translations of error messages keyed by message id.
+///
+/// A message id is the message an error is raised with, so the messages of
+/// `error_ext`, pipeline items and `message` overrides can all be given
+/// translated variants. In an id, `{}` matches the arguments of a formatted
+/// message like `unique value duplicated: email`, which are put into the
+/// `{}` of the translation in order. Messages without a translation are kept
+/// as is.
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalog {
+    messages: BTreeMap<String, BTreeMap<&'static str, String>>,
+}
+
+impl MessageCatalog {
+
+    pub fn new() -> Self {
+        Self { messages: BTreeMap::new() }
+    }
+
+    /// A catalog with the translations of the runtime's built-in messages.
+    pub fn builtin() -> Self {
+        let mut catalog = Self::new();
+        for (id, translations) in BUILTIN_MESSAGES {
+            for (language, text) in BUILTIN_LANGUAGES.iter().zip(translations.iter()) {
+                catalog.define(id, language, *text);
+            }
+        }
+        catalog
+    }
+
+    pub fn define(&mut self, id: &str, language: &Language, text: impl Into<String>) {
+        self.messages.entry(id.to_owned()).or_default().insert(language.as_str(), text.into());
+    }
+
+    pub fn translate(&self, id: &str, language: &Language) -> Option<&str> {
+        self.messages.get(id)?.get(language.as_str()).map(|t| t.as_str())
+    }
+
+    pub fn contains(&self, id: &str, language: &Language) -> bool {
+        self.translate(id, language).is_some()
+    }
+
+    /// Translate `message` with the id equal to it, or else with an id whose
+    /// `{}` placeholders match its arguments.
+    pub fn translate_message(&self, message: &str, language: &Language) -> Option<String> {
+        if let Some(text) = self.translate(message, language) {
+            return Some(text.to_owned());
+        }
+        self.messages.iter().filter(|(id, _)| id.contains("{}")).find_map(|(id, translations)| {
+            let arguments = match_template(id, message)?;
+            let text = translations.get(language.as_str())?;
+            Some(fill_template(text, &arguments))
+        })
+    }
+
+    /// Translate the message and the per path messages of `error`.
+    pub fn localize_error(&self, error: &Error, language: &Language) -> Error {
+        Self::localize_error_in(&[self], error, language)
+    }
+
+    /// Translate `error` with the first catalog from the end which has a
+    /// translation for a message.
+    pub fn localize_error_in(catalogs: &[&MessageCatalog], error: &Error, language: &Language) -> Error {
+        let translate = |message: &str| catalogs.iter().rev().find_map(|c| c.translate_message(message, language)).unwrap_or_else(|| message.to_owned());
+        let mut localized = Error::new_with_code(translate(&error.message), error.code);
+        localized.errors = error.errors.as_ref().map(|errors| errors.iter().map(|(k, v)| (k.to_owned(), translate(v))).collect());
+        localized
+    }
+}
+
+/// The texts `{}` stands for when `message` is formatted from `template`.
+fn match_template<'a>(template: &str, message: &'a str) -> Option<Vec<&'a str>> {
+    let mut parts = template.split("{}");
+    let mut rest = message.strip_prefix(parts.next()?)?;
+    let mut parts = parts.peekable();
+    let mut arguments = vec![];
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            arguments.push(rest.strip_suffix(part)?);
+            rest = "";
+        } else {
+            let end = rest.find(part)?;
+            arguments.push(&rest[..end]);
+            rest = &rest[end + part.len()..];
+        }
+    }
+    rest.is_empty().then_some(arguments)
+}
+
+fn fill_template(template: &str, arguments: &[&str]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut arguments = arguments.iter();
+    let mut parts = template.split("{}").peekable();
+    while let Some(part) = parts.next() {
+        result.push_str(part);
+        if parts.peek().is_some() {
+            result.push_str(arguments.next().copied().unwrap_or("{}"));
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use key_path::path;
+    use serde_json::json;
+    use teo_parser::r#type::Type;
+    use crate::coder::json_to_teon::json_to_teon_with_type;
+    use crate::error_ext;
+    use crate::model::Model;
+    use crate::namespace::Namespace;
+    use super::*;
+
+    fn message_of(error: &Error) -> String {
+        error.errors.as_ref().and_then(|errors| errors.values().next().cloned()).unwrap_or_else(|| error.message.clone())
+    }
+
+    #[test]
+    fn builtin_ids_match_runtime_messages() {
+        let catalog = MessageCatalog::builtin();
+        let namespace = Namespace::main();
+        let mut model = Model::new();
+        model.path = vec!["User".to_owned()];
+        let mut errors = vec![
+            error_ext::unexpected_input(path!["a"]),
+            error_ext::missing_required_input(path!["a"]),
+            error_ext::missing_required_input_with_type(path!["a"], "name"),
+            error_ext::cannot_disconnect_previous_relation(path!["a"]),
+            error_ext::invalid_key_on_model(path!["a"], "name", &model),
+            error_ext::deletion_denied(path!["a"], "posts"),
+            error_ext::updation_denied(path!["a"], "posts"),
+            error_ext::unique_value_duplicated(path!["a"], "email"),
+            error_ext::object_is_not_saved_thus_cant_be_deleted(path!["a"]),
+        ];
+        let mismatches = [
+            (Type::Null, json!(1)),
+            (Type::Bool, json!(1)),
+            (Type::Int, json!("1")),
+            (Type::Int64, json!("1")),
+            (Type::Float32, json!("1")),
+            (Type::Float, json!("1")),
+            (Type::Decimal, json!(true)),
+            (Type::Decimal, json!("one")),
+            (Type::String, json!(1)),
+            (Type::ObjectId, json!(1)),
+            (Type::ObjectId, json!("one")),
+            (Type::Date, json!(1)),
+            (Type::Date, json!("one")),
+            (Type::DateTime, json!(1)),
+            (Type::DateTime, json!("one")),
+            (Type::Array(Box::new(Type::Int)), json!(1)),
+            (Type::Dictionary(Box::new(Type::Int)), json!(1)),
+        ];
+        for (t, json) in mismatches {
+            errors.push(json_to_teon_with_type(&json, &path!["a"], &t, &namespace).unwrap_err());
+        }
+        errors.extend(["prefix of", "suffix of"].map(|kind| Error::new_with_code(format!("input is not {kind} \"{arg}\"", arg = "abc"), 400)));
+        errors.extend(["prefix", "suffix"].map(|kind| Error::new_with_code(format!("input doesn't have {kind} \"{arg}\"", arg = "abc"), 400)));
+        for error in errors {
+            let message = message_of(&error);
+            for language in BUILTIN_LANGUAGES.iter() {
+                assert_ne!(message_of(&catalog.localize_error(&error, language)), message, "{} has no {} translation", message, language.as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn formatted_messages_are_translated_with_their_arguments() {
+        let mut catalog = MessageCatalog::new();
+        catalog.define("key '{}' is invalid on model {}", &Language::De, "Schlüssel '{}' ist für Modell {} ungültig");
+        assert_eq!(catalog.translate_message("key 'name' is invalid on model app.User", &Language::De).as_deref(), Some("Schlüssel 'name' ist für Modell app.User ungültig"));
+        assert_eq!(catalog.translate_message("key 'name' is invalid", &Language::De), None);
+        assert_eq!(catalog.translate_message("key 'name' is invalid on model app.User", &Language::Fr), None);
+    }
+
+    #[test]
+    fn templates_match_their_literal_parts() {
+        assert_eq!(match_template("deletion denied {}", "deletion denied posts"), Some(vec!["posts"]));
+        assert_eq!(match_template("input is not prefix of \"{}\"", "input is not prefix of \"a\"b\""), Some(vec!["a\"b"]));
+        assert_eq!(match_template("{} and {}", "a and b and c"), Some(vec!["a", "b and c"]));
+        assert_eq!(match_template("deletion denied {}", "updation denied posts"), None);
+        assert_eq!(fill_template("{}: {}", &["a", "b"]), "a: b");
+    }
+
+    #[test]
+    fn later_catalogs_take_precedence() {
+        let mut parent = MessageCatalog::new();
+        parent.define("not found", &Language::De, "nicht gefunden");
+        parent.define("invalid", &Language::De, "ungültig");
+        let mut child = MessageCatalog::new();
+        child.define("invalid", &Language::De, "nicht gültig");
+        let mut error = Error::new("not found");
+        error.errors = Some(indexmap! { "id".to_owned() => "invalid".to_owned(), "name".to_owned() => "too long".to_owned() });
+        let localized = MessageCatalog::localize_error_in(&[&parent, &child], &error, &Language::De);
+        assert_eq!(localized.message, "nicht gefunden");
+        let errors = localized.errors.unwrap();
+        assert_eq!(errors.get("id").unwrap(), "nicht gültig");
+        assert_eq!(errors.get("name").unwrap(), "too long");
+        assert_eq!(localized.code, error.code);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod catalog;
+mod builtin;
+
+pub use catalog::MessageCatalog;
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use teo_result::Result;
+use crate::idempotency::store::{IdempotencyRecord, IdempotencyStore, StoredResponse};
+
+/// Keeps idempotency records in the memory of this process.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    records: Mutex<HashMap<String, (IdempotencyRecord, DateTime<Utc>)>>,
+}
+
+impl MemoryStore {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryStore {
+
+    async fn begin(&self, key: &str, fingerprint: &str, expires_at: DateTime<Utc>) -> Result<Option<IdempotencyRecord>> {
+        let now = Utc::now();
+        let mut records = self.records.lock().unwrap();
+        records.retain(|_, (_, expires_at)| *expires_at > now);
+        if let Some((record, _)) = records.get(key) {
+            return Ok(Some(record.clone()));
+        }
+        records.insert(key.to_owned(), (IdempotencyRecord::InFlight { fingerprint: fingerprint.to_owned() }, expires_at));
+        Ok(None)
+    }
+
+    async fn complete(&self, key: &str, response: StoredResponse, expires_at: DateTime<Utc>) -> Result<()> {
+        let mut records = self.records.lock().unwrap();
+        if let Some((record, _)) = records.remove(key) {
+            let fingerprint = record.fingerprint().to_owned();
+            records.insert(key.to_owned(), (IdempotencyRecord::Completed { fingerprint, response }, expires_at));
+        }
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        self.records.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use crate::idempotency::store::{IdempotencyRecord, IdempotencyStore, StoredBody, StoredResponse};
+    use super::MemoryStore;
+
+    fn stored() -> StoredResponse {
+        StoredResponse { code: 201, headers: vec![], body: StoredBody::String("done".to_owned()) }
+    }
+
+    #[tokio::test]
+    async fn begin_reserves_the_key() {
+        let store = MemoryStore::new();
+        let expires_at = Utc::now() + Duration::seconds(60);
+        assert!(store.begin("k", "f", expires_at).await.unwrap().is_none());
+        match store.begin("k", "g", expires_at).await.unwrap() {
+            Some(IdempotencyRecord::InFlight { fingerprint }) => assert_eq!(fingerprint, "f"),
+            record => panic!("unexpected record {:?}", record),
+        }
+    }
+
+    #[tokio::test]
+    async fn complete_keeps_the_response() {
+        let store = MemoryStore::new();
+        let expires_at = Utc::now() + Duration::seconds(60);
+        store.begin("k", "f", expires_at).await.unwrap();
+        store.complete("k", stored(), expires_at).await.unwrap();
+        match store.begin("k", "f", expires_at).await.unwrap() {
+            Some(IdempotencyRecord::Completed { fingerprint, response }) => {
+                assert_eq!(fingerprint, "f");
+                assert_eq!(response.code, 201);
+            }
+            record => panic!("unexpected record {:?}", record),
+        }
+    }
+
+    #[tokio::test]
+    async fn release_and_expiry_free_the_key() {
+        let store = MemoryStore::new();
+        store.begin("k", "f", Utc::now() + Duration::seconds(60)).await.unwrap();
+        store.release("k").await.unwrap();
+        assert!(store.begin("k", "f", Utc::now() - Duration::seconds(1)).await.unwrap().is_none());
+        assert!(store.begin("k", "f", Utc::now() + Duration::seconds(60)).await.unwrap().is_none());
+    }
+}
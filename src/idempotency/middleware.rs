@@ -0,0 +1,243 @@
+use std::sync::Arc;
+use std::time::Duration;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use teo_result::{Error, Result};
+use crate::idempotency::store::{IdempotencyRecord, IdempotencyStore, StoredResponse};
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::request::Ctx;
+use crate::utils::{expires_at, expiry_ttl};
+use crate::value::Value;
+
+#[derive(Debug, Clone)]
+pub struct IdempotencyOptions {
+    pub header_name: String,
+    pub ttl: Duration,
+}
+
+impl Default for IdempotencyOptions {
+
+    fn default() -> Self {
+        Self {
+            header_name: "idempotency-key".to_owned(),
+            ttl: Duration::from_secs(60 * 60 * 24),
+        }
+    }
+}
+
+/// A middleware which runs a mutating request at most once per idempotency
+/// key. Keys are scoped by the handler and the signed in account. A retry
+/// gets the stored response replayed with `idempotent-replayed: true`, a
+/// retry while the first request is running is rejected with 409 and a
+/// request reusing a key with another method, path or body is rejected
+/// with 422. Errors and 5xx responses aren't stored, so they can be retried.
+/// The key is released, too, when the request is cancelled or the response
+/// can't be stored.
+///
+/// It must run inside the identity middleware, which signs in the account
+/// the key is scoped by. A response is never stored when the account is
+/// signed in only after this middleware, since the key was reserved without
+/// the account and another account could be replayed the response.
+pub fn idempotency_middleware(store: Arc<dyn IdempotencyStore>, options: IdempotencyOptions) -> Result<&'static dyn Middleware> {
+    if options.ttl.is_zero() {
+        return Err(Error::new("idempotency ttl must be positive"));
+    }
+    let ttl = expiry_ttl(options.ttl).ok_or_else(|| Error::new("idempotency ttl is too large"))?;
+    let store: &'static Arc<dyn IdempotencyStore> = Box::leak(Box::new(store));
+    let options: &'static IdempotencyOptions = Box::leak(Box::new(options));
+    Ok(Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+        let method = ctx.request().method().to_uppercase();
+        if method == "GET" || method == "HEAD" || method == "OPTIONS" {
+            return next.call(ctx).await;
+        }
+        let Some(key) = ctx.request().headers().get(&options.header_name).map(|k| k.to_owned()) else {
+            return next.call(ctx).await;
+        };
+        if key.is_empty() || key.len() > 255 {
+            return Err(Error::invalid_request_message(format!("invalid {} header", options.header_name)));
+        }
+        let account = account_scope(&ctx)?;
+        let scoped_key = format!("{}:{}:{}", handler_scope(&ctx), account, key);
+        let fingerprint = request_fingerprint(&ctx, &method)?;
+        let expires_at = expires_at(ttl)?;
+        if let Some(record) = store.begin(&scoped_key, &fingerprint, expires_at).await? {
+            if record.fingerprint() != fingerprint {
+                return Err(Error::new_with_code("idempotency key is reused with a different request", 422));
+            }
+            return match record {
+                IdempotencyRecord::InFlight { .. } => Err(Error::new_with_code("a request with this idempotency key is in progress", 409)),
+                IdempotencyRecord::Completed { response, .. } => {
+                    let response = response.to_response();
+                    response.headers().set("idempotent-replayed", "true");
+                    Ok(response)
+                }
+            };
+        }
+        let mut guard = ReleaseOnDrop { store, key: Some(scoped_key.clone()) };
+        let result = next.call(ctx.clone()).await;
+        guard.key = None;
+        let response = match result {
+            Ok(response) => response,
+            Err(error) => {
+                store.release(&scoped_key).await?;
+                return Err(error);
+            }
+        };
+        if account_scope(&ctx)? != account {
+            log::error!("idempotency middleware runs outside the identity middleware, the response of {} isn't stored", scoped_key);
+            store.release(&scoped_key).await?;
+            return Ok(response);
+        }
+        match StoredResponse::from_response(&response) {
+            Some(stored) if response.code() < 500 => if let Err(error) = store.complete(&scoped_key, stored, expires_at).await {
+                log::error!("cannot store the response of idempotency key {}: {}", scoped_key, error.message());
+                store.release(&scoped_key).await?;
+            },
+            _ => store.release(&scoped_key).await?,
+        }
+        Ok(response)
+    })))
+}
+
+/// Releases the key of a request which is dropped before the handler
+/// finishes, e.g. when the client disconnects or a timeout fires.
+struct ReleaseOnDrop {
+    store: &'static Arc<dyn IdempotencyStore>,
+    key: Option<String>,
+}
+
+impl Drop for ReleaseOnDrop {
+
+    fn drop(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let store = self.store;
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(error) = store.release(&key).await {
+                        log::error!("cannot release idempotency key {}: {}", key, error.message());
+                    }
+                });
+            }
+            Err(_) => log::error!("cannot release idempotency key {}: no runtime", key),
+        }
+    }
+}
+
+fn handler_scope(ctx: &Ctx) -> String {
+    let handler_match = ctx.handler_match();
+    let mut path = handler_match.path();
+    path.push(handler_match.handler_name());
+    path.join(".")
+}
+
+/// The identifier of the account signed in by the identity middleware.
+fn account_scope(ctx: &Ctx) -> Result<String> {
+    let data = ctx.data();
+    let Some(account) = data.get::<Value>("account").and_then(Value::as_model_object) else {
+        return Ok(String::new());
+    };
+    let identifier: JsonValue = (&account.identifier()).try_into()?;
+    Ok(format!("{}{}", account.model().path.join("."), identifier))
+}
+
+fn request_fingerprint(ctx: &Ctx, method: &str) -> Result<String> {
+    let body: JsonValue = ctx.body().try_into()?;
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_bytes());
+    hasher.update(b" ");
+    hasher.update(ctx.request().path().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(serde_json::to_vec(&body).unwrap());
+    Ok(URL_SAFE_NO_PAD.encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use teo_result::{Error, Result};
+    use crate::idempotency::{IdempotencyStore, MemoryStore};
+    use crate::idempotency::store::{IdempotencyRecord, StoredResponse};
+    use crate::middleware::next::Next;
+    use crate::request::Ctx;
+    use crate::request::stub::stub_ctx;
+    use crate::response::Response;
+    use super::{idempotency_middleware, IdempotencyOptions};
+
+    /// A memory store which can't keep responses.
+    #[derive(Debug, Default)]
+    struct FailingStore {
+        store: MemoryStore,
+    }
+
+    #[async_trait]
+    impl IdempotencyStore for FailingStore {
+
+        async fn begin(&self, key: &str, fingerprint: &str, expires_at: DateTime<Utc>) -> Result<Option<IdempotencyRecord>> {
+            self.store.begin(key, fingerprint, expires_at).await
+        }
+
+        async fn complete(&self, _key: &str, _response: StoredResponse, _expires_at: DateTime<Utc>) -> Result<()> {
+            Err(Error::new("store is unavailable"))
+        }
+
+        async fn release(&self, key: &str) -> Result<()> {
+            self.store.release(key).await
+        }
+    }
+
+    #[test]
+    fn ttl_must_be_positive() {
+        let options = IdempotencyOptions { ttl: Duration::ZERO, ..IdempotencyOptions::default() };
+        assert!(idempotency_middleware(Arc::new(MemoryStore::new()), options).is_err());
+        assert!(idempotency_middleware(Arc::new(MemoryStore::new()), IdempotencyOptions::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn key_is_released_when_the_response_cannot_be_stored() {
+        let store = Arc::new(FailingStore::default());
+        let middleware = idempotency_middleware(store.clone(), IdempotencyOptions::default()).unwrap();
+        let next: &'static dyn Next = Box::leak(Box::new(|_: Ctx| async { Ok(Response::string("created", "text/plain")) }));
+        let res = middleware.call(stub_ctx("POST", vec![("idempotency-key", "k")]), next).await.unwrap();
+        assert_eq!(res.body().as_text().map(|t| t.as_str()), Some("created"));
+        let expires_at = Utc::now() + chrono::Duration::seconds(60);
+        assert!(store.begin("stub::k", "fingerprint", expires_at).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn completed_responses_are_replayed() {
+        let middleware = idempotency_middleware(Arc::new(MemoryStore::new()), IdempotencyOptions::default()).unwrap();
+        let calls: &'static AtomicUsize = Box::leak(Box::new(AtomicUsize::new(0)));
+        let next: &'static dyn Next = Box::leak(Box::new(move |_: Ctx| async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Response::string("created", "text/plain"))
+        }));
+        let first = middleware.call(stub_ctx("POST", vec![("idempotency-key", "k")]), next).await.unwrap();
+        assert!(first.headers().get("idempotent-replayed").is_none());
+        let second = middleware.call(stub_ctx("POST", vec![("idempotency-key", "k")]), next).await.unwrap();
+        assert_eq!(second.headers().get("idempotent-replayed").as_deref(), Some("true"));
+        assert_eq!(second.body().as_text().map(|t| t.as_str()), Some("created"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn key_is_released_when_the_request_is_dropped() {
+        let store = Arc::new(MemoryStore::new());
+        let middleware = idempotency_middleware(store.clone(), IdempotencyOptions::default()).unwrap();
+        let next: &'static dyn Next = Box::leak(Box::new(|_: Ctx| futures_util::future::pending::<Result<Response>>()));
+        let call = middleware.call(stub_ctx("POST", vec![("idempotency-key", "k")]), next);
+        assert!(tokio::time::timeout(Duration::from_millis(10), call).await.is_err());
+        tokio::task::yield_now().await;
+        let expires_at = Utc::now() + chrono::Duration::seconds(60);
+        assert!(store.begin("stub::k", "fingerprint", expires_at).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod store;
+pub mod memory_store;
+pub mod middleware;
+
+pub use store::{IdempotencyStore, IdempotencyRecord, StoredResponse, StoredBody};
+pub use memory_store::MemoryStore;
+pub use middleware::{idempotency_middleware, IdempotencyOptions};
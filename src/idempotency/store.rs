@@ -0,0 +1,116 @@
+use std::fmt::Debug;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use teo_result::Result;
+use crate::response::body::Body;
+use crate::response::Response;
+use crate::value::Value;
+
+/// What is known about an idempotency key.
+#[derive(Debug, Clone)]
+pub enum IdempotencyRecord {
+    /// The first request with the key is still running.
+    InFlight { fingerprint: String },
+    Completed { fingerprint: String, response: StoredResponse },
+}
+
+impl IdempotencyRecord {
+
+    pub fn fingerprint(&self) -> &str {
+        match self {
+            IdempotencyRecord::InFlight { fingerprint } => fingerprint.as_str(),
+            IdempotencyRecord::Completed { fingerprint, .. } => fingerprint.as_str(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StoredBody {
+    Empty,
+    String(String),
+    Teon(Value),
+}
+
+/// A response kept for replaying. Cookies are not kept.
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: StoredBody,
+}
+
+impl StoredResponse {
+
+    /// `None` for file, stream and event stream bodies, which can't be replayed.
+    pub fn from_response(response: &Response) -> Option<Self> {
+        let body = response.body();
+        let body = if body.is_empty() {
+            StoredBody::Empty
+        } else if let Some(text) = body.as_text() {
+            StoredBody::String(text.clone())
+        } else if let Some(teon) = body.as_teon() {
+            StoredBody::Teon(teon.clone())
+        } else {
+            return None;
+        };
+        let headers = response.headers();
+        Some(Self {
+            code: response.code(),
+            headers: headers.keys().into_iter().filter_map(|k| headers.get(&k).map(|v| (k, v))).collect(),
+            body,
+        })
+    }
+
+    pub fn to_response(&self) -> Response {
+        let response = Response::empty();
+        response.set_code(self.code);
+        for (key, value) in &self.headers {
+            response.headers().set(key, value);
+        }
+        response.set_body(match &self.body {
+            StoredBody::Empty => Body::empty(),
+            StoredBody::String(text) => Body::string(text.clone()),
+            StoredBody::Teon(teon) => Body::teon(teon.clone()),
+        });
+        response
+    }
+}
+
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync + Debug {
+
+    /// Reserve `key` for a request with `fingerprint`. Returns the existing
+    /// unexpired record without reserving if the key is taken.
+    async fn begin(&self, key: &str, fingerprint: &str, expires_at: DateTime<Utc>) -> Result<Option<IdempotencyRecord>>;
+
+    /// Keep the response of the request which reserved `key`.
+    async fn complete(&self, key: &str, response: StoredResponse, expires_at: DateTime<Utc>) -> Result<()>;
+
+    /// Free `key` so that a retry runs the handler again.
+    async fn release(&self, key: &str) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::response::Response;
+    use super::{StoredBody, StoredResponse};
+
+    #[test]
+    fn replays_code_headers_and_body() {
+        let response = Response::string("hello", "text/plain");
+        response.set_code(201);
+        response.headers().set("location", "/items/1");
+        let stored = StoredResponse::from_response(&response).unwrap();
+        assert!(matches!(&stored.body, StoredBody::String(s) if s == "hello"));
+        let replayed = stored.to_response();
+        assert_eq!(replayed.code(), 201);
+        assert_eq!(replayed.headers().get("location").as_deref(), Some("/items/1"));
+        assert_eq!(replayed.body().as_text().map(|t| t.as_str()), Some("hello"));
+    }
+
+    #[test]
+    fn file_bodies_are_not_stored() {
+        let response = Response::file(std::path::PathBuf::from("/tmp/file"));
+        assert!(StoredResponse::from_response(&response).is_none());
+    }
+}
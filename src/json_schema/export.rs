@@ -0,0 +1,77 @@
+use serde_json::{json, Value as JsonValue};
+use teo_parser::r#type::synthesized_shape_reference::SynthesizedShapeReferenceKind;
+use teo_parser::r#type::Type;
+use crate::interface::Interface;
+use crate::model::Model;
+use crate::namespace::Namespace;
+use crate::openapi::schema::{shape_name, SchemaGenerator, ValueEncoding};
+use crate::r#enum::Enum;
+
+const DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A standalone schema of `t`.
+pub fn type_json_schema(main_namespace: &Namespace, t: &Type, encoding: ValueEncoding) -> JsonValue {
+    let mut generator = SchemaGenerator::new(main_namespace, "#/$defs/").with_encoding(encoding);
+    let root = generator.schema_for_type(t);
+    document(root, generator)
+}
+
+/// A standalone schema of an interface. Generic interfaces are resolved
+/// with `generics`.
+pub fn interface_json_schema(main_namespace: &Namespace, interface: &Interface, generics: &Vec<Type>, encoding: ValueEncoding) -> JsonValue {
+    let mut generator = SchemaGenerator::new(main_namespace, "#/$defs/").with_encoding(encoding);
+    let root = if generics.is_empty() {
+        generator.interface_reference(interface)
+    } else {
+        generator.schema_for_shape(&interface.shape_from_generics(generics), Some(interface))
+    };
+    document(root, generator)
+}
+
+pub fn enum_json_schema(main_namespace: &Namespace, e: &Enum) -> JsonValue {
+    let mut generator = SchemaGenerator::new(main_namespace, "#/$defs/");
+    let root = generator.enum_reference(e);
+    document(root, generator)
+}
+
+/// A standalone schema of the records a model outputs.
+pub fn model_json_schema(main_namespace: &Namespace, model: &Model, encoding: ValueEncoding) -> JsonValue {
+    let mut generator = SchemaGenerator::new(main_namespace, "#/$defs/").with_encoding(encoding);
+    let root = generator.model_reference(model);
+    document(root, generator)
+}
+
+/// A standalone schema of one of a model's synthesized inputs, like
+/// `CreateArgs` or `FindManyArgs`.
+pub fn model_input_json_schema(main_namespace: &Namespace, model: &Model, kind: SynthesizedShapeReferenceKind, encoding: ValueEncoding) -> Option<JsonValue> {
+    let t = model.cache.shape.get(kind)?;
+    Some(type_json_schema(main_namespace, t, encoding))
+}
+
+/// Every synthesized input of a model, keyed by shape name in `$defs`.
+pub fn model_inputs_json_schema(main_namespace: &Namespace, model: &Model, encoding: ValueEncoding) -> JsonValue {
+    let mut generator = SchemaGenerator::new(main_namespace, "#/$defs/").with_encoding(encoding);
+    let mut inputs = vec![];
+    for ((kind, without), t) in model.cache.shape.shapes.iter() {
+        let schema = generator.schema_for_type(t);
+        inputs.push((format!("{}.{}", model.path.join("."), shape_name(*kind, without.as_ref())), schema));
+    }
+    for (name, schema) in inputs {
+        if !generator.definitions.contains_key(&name) {
+            generator.definitions.insert(name, schema);
+        }
+    }
+    document(json!({}), generator)
+}
+
+fn document(root: JsonValue, generator: SchemaGenerator) -> JsonValue {
+    let mut document = json!({"$schema": DIALECT});
+    let object = document.as_object_mut().unwrap();
+    if let JsonValue::Object(root) = root {
+        object.extend(root);
+    }
+    if !generator.definitions.is_empty() {
+        object.insert("$defs".to_owned(), json!(generator.definitions));
+    }
+    document
+}
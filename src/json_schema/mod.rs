@@ -0,0 +1,3 @@
+pub mod export;
+
+pub use export::{enum_json_schema, interface_json_schema, model_input_json_schema, model_inputs_json_schema, model_json_schema, type_json_schema};
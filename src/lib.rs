@@ -30,5 +30,13 @@ pub mod data_set;
 pub mod value;
 pub mod error_ext;
 pub mod admin;
+pub mod i18n;
+pub mod json_schema;
+pub mod openapi;
+pub mod graphql;
+pub mod storage;
+pub mod cookie;
+pub mod session;
+pub mod idempotency;
 
 pub use value::Value;
\ No newline at end of file
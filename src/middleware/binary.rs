@@ -0,0 +1,30 @@
+use futures_util::{stream, StreamExt};
+use crate::coder::binary::{teon_to_binary, BinaryFormat};
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::request::Ctx;
+use crate::response::body::Body;
+use crate::response::body::stream::Stream as BodyStream;
+
+/// A middleware which encodes teon response bodies as MessagePack or CBOR
+/// when the `Accept` header prefers them over json. It's only installed when
+/// the server config enables `binary`.
+pub(crate) fn binary_response_middleware() -> &'static dyn Middleware {
+    Box::leak(Box::new(|ctx: Ctx, next: &'static dyn Next| async move {
+        let format = ctx.request().headers().get("accept").and_then(BinaryFormat::from_accept);
+        let res = next.call(ctx).await?;
+        let Some(format) = format else {
+            return Ok(res);
+        };
+        let body = res.body();
+        let Some(value) = body.as_teon() else {
+            return Ok(res);
+        };
+        let bytes = teon_to_binary(value, format)?;
+        let length = bytes.len() as u64;
+        res.set_body(Body::stream(BodyStream::new(stream::once(async move { Ok(bytes) }).boxed(), format.content_type(), Some(length))));
+        res.headers().set("content-type", format.content_type());
+        res.headers().append("vary", "accept");
+        Ok(res)
+    }))
+}
@@ -0,0 +1,278 @@
+use std::io::Write;
+use brotli::CompressorWriter;
+use flate2::Compression as FlateCompression;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use futures_util::{future, stream, StreamExt};
+use futures_util::stream::BoxStream;
+use serde_json::Value as JsonValue;
+use teo_result::{Error, Result};
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+use crate::config::server::{Compression, Encoding};
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::request::Ctx;
+use crate::response::body::{Body, BodyInner};
+use crate::response::body::stream::Stream as BodyStream;
+use crate::response::Response;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A middleware which compresses string, teon, file and stream bodies with
+/// the encoding negotiated from `Accept-Encoding`. Files and streams are
+/// compressed chunk by chunk. It's installed outside of the binary response
+/// middleware, so MessagePack and CBOR bodies are compressed, too.
+pub(crate) fn compression_middleware(compression: Compression) -> &'static dyn Middleware {
+    let compression: &'static Compression = Box::leak(Box::new(compression));
+    Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+        let accept_encoding = ctx.request().headers().get("accept-encoding").map(|a| a.to_owned());
+        let res = next.call(ctx).await?;
+        match accept_encoding.as_deref().and_then(|a| negotiate_encoding(a, &compression.encodings)) {
+            Some(encoding) => compress_response(res, encoding, compression.threshold).await,
+            None => Ok(res),
+        }
+    }))
+}
+
+/// Pick the encoding with the highest quality value. On a tie, the order of
+/// `supported` decides.
+pub fn negotiate_encoding(accept_encoding: &str, supported: &Vec<Encoding>) -> Option<Encoding> {
+    let mut wildcard: Option<f32> = None;
+    let mut qualities: Vec<(&str, f32)> = vec![];
+    for item in accept_encoding.split(',') {
+        let mut parts = item.split(';');
+        let name = parts.next().unwrap_or("").trim().to_lowercase();
+        let quality = parts.find_map(|p| p.trim().strip_prefix("q=").and_then(|q| q.parse::<f32>().ok())).unwrap_or(1.0);
+        if name == "*" {
+            wildcard = Some(quality);
+        } else if let Some(encoding) = Encoding::from_name(&name) {
+            qualities.push((encoding.name(), quality));
+        }
+    }
+    let mut result: Option<(Encoding, f32)> = None;
+    for encoding in supported {
+        let quality = qualities.iter().find(|(n, _)| *n == encoding.name()).map(|(_, q)| *q).or(wildcard).unwrap_or(0.0);
+        if quality > 0.0 && result.map_or(true, |(_, q)| quality > q) {
+            result = Some((*encoding, quality));
+        }
+    }
+    result.map(|(e, _)| e)
+}
+
+async fn compress_response(res: Response, encoding: Encoding, threshold: usize) -> Result<Response> {
+    if res.headers().contains_key("content-encoding") {
+        return Ok(res);
+    }
+    let body = res.body();
+    let (stream, content_type) = match body.inner.as_ref() {
+        BodyInner::String(s) => {
+            let content_type = res.headers().get("content-type").unwrap_or("text/plain".to_owned());
+            if s.len() < threshold || is_compressed_content_type(&content_type) {
+                return Ok(res);
+            }
+            (stream::once(future::ready(Ok(s.as_bytes().to_vec()))).boxed(), content_type)
+        }
+        BodyInner::Teon(value) => {
+            let json: JsonValue = value.try_into()?;
+            let bytes = serde_json::to_vec(&json).unwrap();
+            if bytes.len() < threshold {
+                return Ok(res);
+            }
+            let content_type = res.headers().get("content-type").unwrap_or_else(|| "application/json".to_owned());
+            (stream::once(future::ready(Ok(bytes))).boxed(), content_type)
+        }
+        BodyInner::File(path) => {
+            let content_type = res.headers().get("content-type").unwrap_or_else(|| mime_guess::from_path(path).first_or_octet_stream().to_string());
+            if is_compressed_content_type(&content_type) {
+                return Ok(res);
+            }
+            let metadata = tokio::fs::metadata(path).await.map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            if (metadata.len() as usize) < threshold {
+                return Ok(res);
+            }
+            let file = File::open(path).await.map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+            (file_stream(file), content_type)
+        }
+        BodyInner::Stream(body_stream) => {
+            let content_type = res.headers().get("content-type").unwrap_or_else(|| body_stream.content_type().to_owned());
+            if is_compressed_content_type(&content_type) || body_stream.length().is_some_and(|length| (length as usize) < threshold) {
+                return Ok(res);
+            }
+            let Some(stream) = body_stream.take() else {
+                return Ok(res);
+            };
+            (stream, content_type)
+        }
+        _ => return Ok(res),
+    };
+    res.set_body(Body::stream(BodyStream::new(compress_stream(stream, encoding), &content_type, None)));
+    res.headers().set("content-type", content_type);
+    res.headers().set("content-encoding", encoding.name());
+    res.headers().append("vary", "accept-encoding");
+    Ok(res)
+}
+
+/// Read a file in chunks instead of loading it into memory.
+fn file_stream(file: File) -> BoxStream<'static, Result<Vec<u8>>> {
+    stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(length) => {
+                buffer.truncate(length);
+                Some((Ok(buffer), Some(file)))
+            }
+            Err(e) => Some((Err(Error::internal_server_error_message(e.to_string())), None)),
+        }
+    }).boxed()
+}
+
+/// Compress chunk by chunk. The encoder is flushed after each chunk, so
+/// streamed lines like NDJSON reach the client without waiting for later
+/// chunks. The encoder is finished when the input ends.
+fn compress_stream(input: BoxStream<'static, Result<Vec<u8>>>, encoding: Encoding) -> BoxStream<'static, Result<Vec<u8>>> {
+    stream::unfold(Some((input, Encoder::new(encoding))), |state| async move {
+        let (mut input, mut encoder) = state?;
+        loop {
+            match input.next().await {
+                Some(Ok(chunk)) => match encoder.write(&chunk) {
+                    Ok(output) if output.is_empty() => continue,
+                    Ok(output) => return Some((Ok(output), Some((input, encoder)))),
+                    Err(e) => return Some((Err(Error::internal_server_error_message(e.to_string())), None)),
+                },
+                Some(Err(e)) => return Some((Err(e), None)),
+                None => return Some((encoder.finish().map_err(|e| Error::internal_server_error_message(e.to_string())), None)),
+            }
+        }
+    }).boxed()
+}
+
+enum Encoder {
+    Brotli(CompressorWriter<Vec<u8>>),
+    Gzip(GzEncoder<Vec<u8>>),
+    Deflate(DeflateEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+
+    fn new(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Brotli => Encoder::Brotli(CompressorWriter::new(Vec::new(), 4096, 5, 22)),
+            Encoding::Gzip => Encoder::Gzip(GzEncoder::new(Vec::new(), FlateCompression::default())),
+            Encoding::Deflate => Encoder::Deflate(DeflateEncoder::new(Vec::new(), FlateCompression::default())),
+        }
+    }
+
+    /// Feed a chunk, flush it and take the output.
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+        Ok(match self {
+            Encoder::Brotli(writer) => {
+                writer.write_all(bytes)?;
+                writer.flush()?;
+                std::mem::take(writer.get_mut())
+            }
+            Encoder::Gzip(encoder) => {
+                encoder.write_all(bytes)?;
+                encoder.flush()?;
+                std::mem::take(encoder.get_mut())
+            }
+            Encoder::Deflate(encoder) => {
+                encoder.write_all(bytes)?;
+                encoder.flush()?;
+                std::mem::take(encoder.get_mut())
+            }
+        })
+    }
+
+    fn finish(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoder::Brotli(writer) => Ok(writer.into_inner()),
+            Encoder::Gzip(encoder) => encoder.finish(),
+            Encoder::Deflate(encoder) => encoder.finish(),
+        }
+    }
+}
+
+fn is_compressed_content_type(content_type: &str) -> bool {
+    let content_type = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    if content_type == "image/svg+xml" {
+        return false;
+    }
+    content_type.starts_with("image/") || content_type.starts_with("video/") || content_type.starts_with("audio/") || matches!(content_type.as_str(),
+        "application/zip" | "application/gzip" | "application/x-gzip" | "application/x-bzip2" |
+        "application/x-7z-compressed" | "application/x-rar-compressed" | "application/zstd" |
+        "application/x-xz" | "font/woff" | "font/woff2"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use flate2::read::GzDecoder;
+    use super::*;
+
+    #[test]
+    fn negotiates_by_quality_then_server_order() {
+        let supported = vec![Encoding::Brotli, Encoding::Gzip, Encoding::Deflate];
+        assert_eq!(negotiate_encoding("gzip, deflate, br", &supported), Some(Encoding::Brotli));
+        assert_eq!(negotiate_encoding("gzip;q=1.0, br;q=0.5", &supported), Some(Encoding::Gzip));
+        assert_eq!(negotiate_encoding("br;q=0, *;q=0.1", &supported), Some(Encoding::Gzip));
+        assert_eq!(negotiate_encoding("identity", &supported), None);
+        assert_eq!(negotiate_encoding("br", &vec![Encoding::Gzip]), None);
+    }
+
+    #[test]
+    fn skips_already_compressed_content_types() {
+        assert!(is_compressed_content_type("image/png"));
+        assert!(is_compressed_content_type("application/zip"));
+        assert!(!is_compressed_content_type("image/svg+xml"));
+        assert!(!is_compressed_content_type("application/msgpack"));
+        assert!(!is_compressed_content_type("application/json; charset=utf-8"));
+    }
+
+    #[tokio::test]
+    async fn compresses_streams_chunk_by_chunk() {
+        let chunks: Vec<Result<Vec<u8>>> = (0..100).map(|i| Ok(format!("chunk {} ", i).into_bytes())).collect();
+        let expected: Vec<u8> = (0..100).flat_map(|i| format!("chunk {} ", i).into_bytes()).collect();
+        let compressed: Vec<u8> = compress_stream(stream::iter(chunks).boxed(), Encoding::Gzip)
+            .map(|chunk| chunk.unwrap())
+            .concat()
+            .await;
+        let mut decompressed = vec![];
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut decompressed).unwrap();
+        assert_eq!(decompressed, expected);
+    }
+
+    #[tokio::test]
+    async fn each_chunk_is_flushed() {
+        let input = stream::iter(vec![Ok(b"{\"id\":1}\n".to_vec())]).chain(stream::pending()).boxed();
+        let mut output = compress_stream(input, Encoding::Gzip);
+        let first = output.next().await.unwrap().unwrap();
+        let mut decoder = flate2::write::GzDecoder::new(vec![]);
+        decoder.write_all(&first).unwrap();
+        decoder.flush().unwrap();
+        assert_eq!(decoder.get_ref().as_slice(), b"{\"id\":1}\n");
+    }
+
+    #[tokio::test]
+    async fn keeps_the_content_type_of_teon_bodies() {
+        let res = Response::teon(crate::value::Value::String("problem".repeat(10)));
+        res.headers().set("content-type", "application/problem+json");
+        let res = compress_response(res, Encoding::Gzip, 0).await.unwrap();
+        assert_eq!(res.headers().get("content-type").as_deref(), Some("application/problem+json"));
+        assert_eq!(res.headers().get("content-encoding").as_deref(), Some("gzip"));
+        let res = compress_response(Response::teon(crate::value::Value::Bool(true)), Encoding::Gzip, 0).await.unwrap();
+        assert_eq!(res.headers().get("content-type").as_deref(), Some("application/json"));
+    }
+
+    #[tokio::test]
+    async fn streams_files() {
+        let path = std::env::temp_dir().join(format!("teo-compression-{}", uuid::Uuid::new_v4()));
+        let content = vec![b'a'; CHUNK_SIZE * 2 + 10];
+        tokio::fs::write(&path, &content).await.unwrap();
+        let chunks: Vec<Vec<u8>> = file_stream(File::open(&path).await.unwrap()).map(|chunk| chunk.unwrap()).collect().await;
+        tokio::fs::remove_file(&path).await.unwrap();
+        assert_eq!(chunks.concat(), content);
+    }
+}
@@ -0,0 +1,122 @@
+use regex::Regex;
+use serde::Serialize;
+use crate::handler::handler::Method;
+use crate::request::Request;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct Condition {
+    #[serde(rename = "includePaths")]
+    pub include_paths: Vec<String>,
+    #[serde(rename = "excludePaths")]
+    pub exclude_paths: Vec<String>,
+    pub methods: Vec<Method>,
+}
+
+impl Condition {
+
+    pub fn is_empty(&self) -> bool {
+        self.include_paths.is_empty() && self.exclude_paths.is_empty() && self.methods.is_empty()
+    }
+
+    pub(crate) fn matcher(&self) -> ConditionMatcher {
+        ConditionMatcher {
+            include_paths: self.include_paths.iter().map(|p| path_pattern_regex(p)).collect(),
+            exclude_paths: self.exclude_paths.iter().map(|p| path_pattern_regex(p)).collect(),
+            methods: self.methods.clone(),
+        }
+    }
+}
+
+pub(crate) struct ConditionMatcher {
+    include_paths: Vec<Regex>,
+    exclude_paths: Vec<Regex>,
+    methods: Vec<Method>,
+}
+
+impl ConditionMatcher {
+
+    pub(crate) fn matches(&self, request: &Request) -> bool {
+        if !self.methods.is_empty() && !self.methods.iter().any(|m| m.capitalized_name().eq_ignore_ascii_case(request.method())) {
+            return false;
+        }
+        let path = request.path();
+        if !self.include_paths.is_empty() && !self.include_paths.iter().any(|r| r.is_match(path)) {
+            return false;
+        }
+        !self.exclude_paths.iter().any(|r| r.is_match(path))
+    }
+}
+
+/// `*` matches within a single path segment and `**` matches across segments, e.g. `/admin/**`
+/// matches every path under `/admin`. It doesn't match `/admin` itself, list both patterns to
+/// cover it. Other characters match literally.
+fn path_pattern_regex(pattern: &str) -> Regex {
+    let mut regex_string = "^".to_owned();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '*' {
+            if chars.peek() == Some(&'*') {
+                chars.next();
+                regex_string.push_str(".*");
+            } else {
+                regex_string.push_str("[^/]*");
+            }
+        } else {
+            regex_string.push_str(&regex::escape(&c.to_string()));
+        }
+    }
+    regex_string.push('$');
+    Regex::new(&regex_string).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handler::handler::Method;
+    use crate::request::stub::stub_request;
+    use super::{path_pattern_regex, Condition};
+
+    #[test]
+    fn single_star_stays_within_a_segment() {
+        let regex = path_pattern_regex("/users/*/posts");
+        assert!(regex.is_match("/users/1/posts"));
+        assert!(regex.is_match("/users//posts"));
+        assert!(!regex.is_match("/users/1/2/posts"));
+    }
+
+    #[test]
+    fn double_star_crosses_segments() {
+        let regex = path_pattern_regex("/admin/**");
+        assert!(regex.is_match("/admin/"));
+        assert!(regex.is_match("/admin/users/1"));
+        assert!(!regex.is_match("/admin"));
+        assert!(!regex.is_match("/administrator"));
+    }
+
+    #[test]
+    fn regex_metacharacters_match_literally() {
+        let regex = path_pattern_regex("/files/a.b+(c)");
+        assert!(regex.is_match("/files/a.b+(c)"));
+        assert!(!regex.is_match("/files/aXb+(c)"));
+        assert!(!regex.is_match("/files/a.bb(c)"));
+    }
+
+    #[test]
+    fn filters_by_method_and_paths() {
+        let matcher = Condition {
+            include_paths: vec!["/api/**".to_owned()],
+            exclude_paths: vec!["/api/health".to_owned()],
+            methods: vec![Method::Post],
+        }.matcher();
+        assert!(matcher.matches(&stub_request("POST", "/api/users", vec![])));
+        assert!(matcher.matches(&stub_request("post", "/api/users", vec![])));
+        assert!(!matcher.matches(&stub_request("GET", "/api/users", vec![])));
+        assert!(!matcher.matches(&stub_request("POST", "/api/health", vec![])));
+        assert!(!matcher.matches(&stub_request("POST", "/users", vec![])));
+    }
+
+    #[test]
+    fn empty_condition_matches_everything() {
+        let matcher = Condition::default().matcher();
+        assert!(matcher.matches(&stub_request("DELETE", "/anything", vec![])));
+    }
+}
@@ -3,10 +3,13 @@ use educe::Educe;
 use serde::Serialize;
 use super::creator::Creator;
 
-#[derive(Educe, Serialize)]
+#[derive(Educe, Serialize, Clone)]
 #[educe(Debug)]
 pub struct Definition {
     pub path: Vec<String>,
     #[educe(Debug(ignore))] #[serde(skip)]
     pub creator: Arc<dyn Creator>,
 }
+
+unsafe impl Send for Definition { }
+unsafe impl Sync for Definition { }
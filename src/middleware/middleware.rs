@@ -1,5 +1,6 @@
 use std::future::Future;
 use futures_util::future::BoxFuture;
+use crate::middleware::condition::Condition;
 use crate::middleware::next::Next;
 use crate::request::ctx::Ctx;
 use crate::request::ctx::extract::ExtractFromRequestCtx;
@@ -41,7 +42,18 @@ pub(crate) fn combine_middleware(middlewares: Vec<&'static dyn Middleware>) -> &
     }
 }
 
-fn join_middleware(outer: &'static dyn Middleware, inner: &'static dyn Middleware) -> &'static dyn Middleware {
+pub(crate) fn conditional_middleware(middleware: &'static dyn Middleware, condition: &Condition) -> &'static dyn Middleware {
+    let matcher = Box::leak(Box::new(condition.matcher()));
+    Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+        if matcher.matches(ctx.request()) {
+            middleware.call(ctx, next).await
+        } else {
+            next.call(ctx).await
+        }
+    }))
+}
+
+pub(crate) fn join_middleware(outer: &'static dyn Middleware, inner: &'static dyn Middleware) -> &'static dyn Middleware {
     return Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
         outer.call(ctx, Box::leak(Box::new(move |ctx: Ctx| async move {
             inner.call(ctx, next).await
@@ -4,7 +4,12 @@ pub mod creator;
 pub mod definition;
 pub mod r#use;
 pub mod block;
+pub mod condition;
+pub mod compression;
+pub mod binary;
+pub mod timeout;
 
 pub use definition::Definition;
 pub use r#use::Use;
-pub use block::Block;
\ No newline at end of file
+pub use block::Block;
+pub use condition::Condition;
\ No newline at end of file
@@ -0,0 +1,78 @@
+use std::time::{Duration, Instant};
+use teo_result::{Error, Result};
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::request::Ctx;
+use crate::value::Value;
+
+/// A middleware which cancels the rest of the stack with 504 when it runs
+/// longer than `duration`. A transaction that is running when the future is
+/// dropped is aborted.
+pub(crate) fn timeout_middleware(duration: Duration) -> &'static dyn Middleware {
+    Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+        ctx.set_deadline(Instant::now() + duration);
+        match tokio::time::timeout(duration, next.call(ctx)).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::new_with_code(format!("request timed out after {}ms", duration.as_millis()), 504)),
+        }
+    }))
+}
+
+/// A timeout given as an int or float amount of seconds, like `30` or `0.5`.
+pub(crate) fn timeout_from_seconds(seconds: &Value) -> Result<Duration> {
+    let Some(seconds) = seconds.to_float() else {
+        return Err(Error::new("timeout should be an amount of seconds"));
+    };
+    if seconds.is_nan() || seconds <= 0.0 {
+        return Err(Error::new("timeout should be positive"));
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|_| Error::new("timeout is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use teo_result::Result;
+    use crate::middleware::next::Next;
+    use crate::request::Ctx;
+    use crate::request::stub::stub_ctx;
+    use crate::response::Response;
+    use crate::value::Value;
+    use super::{timeout_from_seconds, timeout_middleware};
+
+    #[test]
+    fn accepts_fractional_seconds() {
+        assert_eq!(timeout_from_seconds(&Value::Int(2)).unwrap(), Duration::from_secs(2));
+        assert_eq!(timeout_from_seconds(&Value::Float(0.25)).unwrap(), Duration::from_millis(250));
+        assert!(timeout_from_seconds(&Value::Int(0)).is_err());
+        assert!(timeout_from_seconds(&Value::Float(-1.0)).is_err());
+        assert!(timeout_from_seconds(&Value::Float(f64::NAN)).is_err());
+        assert!(timeout_from_seconds(&Value::Float(f64::INFINITY)).is_err());
+        assert!(timeout_from_seconds(&Value::String("1".to_owned())).is_err());
+    }
+
+    #[tokio::test]
+    async fn slow_requests_fail_with_504() {
+        let next: &'static dyn Next = Box::leak(Box::new(|_: Ctx| futures_util::future::pending::<Result<Response>>()));
+        let error = timeout_middleware(Duration::from_millis(10)).call(stub_ctx("GET", vec![]), next).await.unwrap_err();
+        assert_eq!(error.code, 504);
+    }
+
+    #[tokio::test]
+    async fn sets_the_earliest_deadline() {
+        let next: &'static dyn Next = Box::leak(Box::new(|ctx: Ctx| async move {
+            assert!(ctx.deadline().is_some());
+            assert!(!ctx.is_timed_out());
+            Ok(Response::empty())
+        }));
+        let ctx = stub_ctx("GET", vec![]);
+        let outer = timeout_middleware(Duration::from_secs(60));
+        let inner = timeout_middleware(Duration::from_secs(1));
+        let inner_next: &'static dyn Next = Box::leak(Box::new(move |ctx: Ctx| inner.call(ctx, next)));
+        outer.call(ctx.clone(), inner_next).await.unwrap();
+        let deadline = ctx.deadline().unwrap();
+        assert!(deadline <= std::time::Instant::now() + Duration::from_secs(1));
+        ctx.set_deadline(std::time::Instant::now() + Duration::from_secs(120));
+        assert_eq!(ctx.deadline().unwrap(), deadline);
+    }
+}
@@ -1,8 +1,13 @@
 use std::sync::Arc;
 use educe::Educe;
 use serde::Serialize;
+use teo_result::{Error, Result};
 use crate::arguments::Arguments;
+use crate::handler::handler::Method;
+use crate::middleware::condition::Condition;
 use crate::middleware::creator::Creator;
+use crate::middleware::middleware::{conditional_middleware, Middleware};
+use crate::value::Value;
 
 #[derive(Educe)]
 #[educe(Debug)]
@@ -12,7 +17,63 @@ pub struct Use {
     #[educe(Debug(ignore))] #[serde(skip)]
     pub creator: Arc<dyn Creator>,
     pub arguments: Arguments,
+    pub condition: Condition,
+    pub priority: i64,
+}
+
+impl Use {
+
+    /// Create a middleware use. The reserved arguments `includePaths`, `excludePaths`, `methods`
+    /// and `priority` are taken out of the arguments and configure when and in which order the
+    /// middleware runs.
+    pub fn new(path: Vec<String>, creator: Arc<dyn Creator>, arguments: Arguments) -> Result<Self> {
+        let mut condition = Condition::default();
+        let mut priority = 0;
+        let mut rest = vec![];
+        for (key, value) in arguments.iter() {
+            match key.as_str() {
+                "includePaths" => condition.include_paths = string_array_argument(key, value)?,
+                "excludePaths" => condition.exclude_paths = string_array_argument(key, value)?,
+                "methods" => {
+                    let Some(array) = value.as_array() else {
+                        return Err(Error::new("argument 'methods' should be an array of methods"));
+                    };
+                    condition.methods = array.iter().map(|v| Method::try_from(v)).collect::<Result<Vec<Method>>>()?;
+                }
+                "priority" => {
+                    let Some(value) = value.to_int64() else {
+                        return Err(Error::new("argument 'priority' should be an integer"));
+                    };
+                    priority = value;
+                }
+                _ => rest.push((key.clone(), value.clone())),
+            }
+        }
+        Ok(Self {
+            path,
+            creator,
+            arguments: Arguments::new(rest.into_iter().collect()),
+            condition,
+            priority,
+        })
+    }
+
+    pub async fn create(&self) -> Result<&'static dyn Middleware> {
+        let middleware = self.creator.call(self.arguments.clone()).await?;
+        if self.condition.is_empty() {
+            Ok(middleware)
+        } else {
+            Ok(conditional_middleware(middleware, &self.condition))
+        }
+    }
+}
+
+fn string_array_argument(key: &str, value: &Value) -> Result<Vec<String>> {
+    let Some(array) = value.as_array() else {
+        return Err(Error::new(format!("argument '{}' should be an array of strings", key)));
+    };
+    array.iter().map(|v| v.as_str().map(|s| s.to_owned()).ok_or(Error::new(format!("argument '{}' should be an array of strings", key)))).collect()
 }
 
 unsafe impl Send for Use { }
-unsafe impl Sync for Use { }
\ No newline at end of file
+unsafe impl Sync for Use { }
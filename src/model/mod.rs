@@ -7,6 +7,7 @@ pub mod index;
 pub mod migration;
 pub mod model;
 pub mod ctx;
+pub mod subscription;
 
 pub use model::Model;
 pub use object::Object;
@@ -12,6 +12,8 @@ use tokio::sync::Mutex as TokioMutex;
 use crate::action::Action;
 use crate::connection::transaction;
 use crate::model::{Field, Model};
+use crate::model::subscription::{self, ChangeKind};
+use crate::storage::FileStorage;
 use key_path::{path, KeyPath};
 use crate::traits::named::Named;
 use async_recursion::async_recursion;
@@ -717,9 +719,110 @@ impl Object {
                 }
             }
         }
+        self.delete_stored_files_after_commit(self.stored_files()).await;
+        self.publish_after_commit(ChangeKind::Delete).await;
         Ok(())
     }
 
+    /// Subscribers only hear about changes which are committed.
+    async fn publish_after_commit(&self, kind: ChangeKind) {
+        let object = self.clone();
+        self.transaction_ctx().after_commit(move || async move {
+            subscription::publish(kind, &object);
+        }).await;
+    }
+
+    fn storage_for_field(&self, field: &Field) -> Option<(&'static Arc<dyn FileStorage>, bool)> {
+        let name = field.data.get("storage:name")?.as_str()?;
+        let url = field.data.get("storage:url").map_or(false, |u| u.as_bool().unwrap_or(false));
+        let storage = self.namespace().storage_at_path(&name.split(".").collect())?;
+        Some((storage, url))
+    }
+
+    fn storage_key(storage: &Arc<dyn FileStorage>, url: bool, stored: &str) -> Option<String> {
+        if url {
+            storage.key_for_url(stored)
+        } else {
+            Some(stored.to_owned())
+        }
+    }
+
+    /// Upload the incoming files and replace them with their stored keys or urls. When an
+    /// upload fails, the files uploaded before it are deleted.
+    async fn put_files_to_storages(&self, path: &KeyPath) -> teo_result::Result<Vec<(&'static Arc<dyn FileStorage>, String)>> {
+        let mut uploaded = vec![];
+        for field in self.model().fields() {
+            if !field.data.contains_key("storage:name") {
+                continue
+            }
+            let Ok(Value::File(file)) = self.get_value(field.name()) else {
+                continue
+            };
+            let Some((storage, url)) = self.storage_for_field(field) else {
+                delete_stored_files(uploaded).await;
+                return Err(Error::internal_server_error_pathed(path + field.name(), "storage is not defined"));
+            };
+            let key = match storage.put(&file).await {
+                Ok(key) => key,
+                Err(error) => {
+                    delete_stored_files(uploaded).await;
+                    return Err(Error::internal_server_error_pathed(path + field.name(), error.message()));
+                }
+            };
+            let stored = if url { storage.url(&key) } else { key.clone() };
+            uploaded.push((storage, key));
+            self.inner.value_map.lock().unwrap().insert(field.name().to_owned(), Value::String(stored));
+            self.set_modified_field(field.name());
+        }
+        Ok(uploaded)
+    }
+
+    /// The stored files of this object which are replaced by this save.
+    fn replaced_stored_files(&self) -> Vec<(&'static Arc<dyn FileStorage>, String)> {
+        let mut result = vec![];
+        for field in self.model().fields() {
+            let Some((storage, url)) = self.storage_for_field(field) else {
+                continue
+            };
+            let previous = self.inner.previous_value_map.lock().unwrap().remove(field.name());
+            let Some(Value::String(previous)) = previous else {
+                continue
+            };
+            if self.get_value(field.name()).ok().as_ref().and_then(|v| v.as_str()) == Some(previous.as_str()) {
+                continue
+            }
+            if let Some(key) = Self::storage_key(storage, url, &previous) {
+                result.push((storage, key));
+            }
+        }
+        result
+    }
+
+    /// The stored files of this object.
+    fn stored_files(&self) -> Vec<(&'static Arc<dyn FileStorage>, String)> {
+        let mut result = vec![];
+        for field in self.model().fields() {
+            let Some((storage, url)) = self.storage_for_field(field) else {
+                continue
+            };
+            let Ok(Value::String(stored)) = self.get_value(field.name()) else {
+                continue
+            };
+            if let Some(key) = Self::storage_key(storage, url, &stored) {
+                result.push((storage, key));
+            }
+        }
+        result
+    }
+
+    /// Blobs are only deleted once the change which drops them is committed.
+    async fn delete_stored_files_after_commit(&self, files: Vec<(&'static Arc<dyn FileStorage>, String)>) {
+        if files.is_empty() {
+            return
+        }
+        self.transaction_ctx().after_commit(move || delete_stored_files(files)).await;
+    }
+
     #[async_recursion]
     async fn save_to_database(&self, path: &KeyPath) -> teo_result::Result<()> {
         if !self.is_new() && self.is_modified() {
@@ -840,10 +943,21 @@ impl Object {
         if is_modified || is_new {
             // apply pipeline
             self.apply_on_save_pipeline_and_validate_required_fields(path, ignore_required_relation).await?;
-            self.trigger_before_save_callbacks(path).await?;
-            // perform relation manipulations (has foreign key)
-            self.perform_relation_manipulations(|r| r.has_foreign_key, path, is_new, is_modified).await?;
-            self.save_to_database(path).await?;
+            // persist uploaded files
+            let uploaded = self.put_files_to_storages(path).await?;
+            let result = async {
+                self.trigger_before_save_callbacks(path).await?;
+                // perform relation manipulations (has foreign key)
+                self.perform_relation_manipulations(|r| r.has_foreign_key, path, is_new, is_modified).await?;
+                self.save_to_database(path).await
+            }.await;
+            if let Err(error) = result {
+                delete_stored_files(uploaded).await;
+                return Err(error);
+            }
+            if !uploaded.is_empty() {
+                self.transaction_ctx().after_abort(move || delete_stored_files(uploaded));
+            }
         } else {
             // perform relation manipulations (has foreign key)
             self.perform_relation_manipulations(|r| r.has_foreign_key, path, is_new, is_modified).await?;
@@ -854,6 +968,8 @@ impl Object {
         self.clear_state();
         if is_modified || is_new {
             self.trigger_after_save_callbacks(path).await?;
+            self.delete_stored_files_after_commit(self.replaced_stored_files()).await;
+            self.publish_after_commit(if is_new { ChangeKind::Insert } else { ChangeKind::Update }).await;
         }
         Ok(())
     }
@@ -1867,6 +1983,14 @@ impl PartialEq for Object {
     }
 }
 
+async fn delete_stored_files(files: Vec<(&'static Arc<dyn FileStorage>, String)>) {
+    for (storage, key) in files {
+        if let Err(error) = storage.delete(&key).await {
+            log::warn!("cannot delete file `{}`: {}", key, error.message());
+        }
+    }
+}
+
 unsafe impl Send for Object { }
 unsafe impl Sync for Object { }
 
@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use futures_util::{Stream, StreamExt, stream};
+use indexmap::indexmap;
+use key_path::path;
+use once_cell::sync::Lazy;
+use serde_json::Value as JsonValue;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio::sync::broadcast::error::RecvError;
+use teo_result::Result;
+use crate::action::action::{FIND_FIRST_HANDLER, FIND_MANY_HANDLER};
+use crate::model::{Model, Object};
+use crate::request;
+use crate::response::body::sse::Event;
+use crate::value::Value;
+
+const CHANNEL_CAPACITY: usize = 1024;
+
+static CHANNELS: Lazy<Mutex<BTreeMap<Vec<String>, Sender<Change>>>> = Lazy::new(|| {
+    Mutex::new(BTreeMap::new())
+});
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl ChangeKind {
+
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            ChangeKind::Insert => "insert",
+            ChangeKind::Update => "update",
+            ChangeKind::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    pub object: Object,
+}
+
+/// Notify subscribers of the object's model about a change. This is a no-op
+/// when nobody is listening.
+pub(crate) fn publish(kind: ChangeKind, object: &Object) {
+    let mut channels = CHANNELS.lock().unwrap();
+    if let Some(sender) = channels.get(&object.model().path) {
+        if sender.receiver_count() > 0 {
+            let _ = sender.send(Change { kind, object: object.clone() });
+        } else {
+            channels.remove(&object.model().path);
+        }
+    }
+}
+
+/// Remove the channel of `model_path` once its last receiver is gone.
+fn prune(model_path: &Vec<String>) {
+    let mut channels = CHANNELS.lock().unwrap();
+    if channels.get(model_path).is_some_and(|sender| sender.receiver_count() == 0) {
+        channels.remove(model_path);
+    }
+}
+
+/// Receive every change made to objects of `model`.
+pub fn changes(model: &Model) -> Receiver<Change> {
+    let mut channels = CHANNELS.lock().unwrap();
+    if let Some(sender) = channels.get(&model.path) {
+        sender.subscribe()
+    } else {
+        let (sender, receiver) = broadcast::channel(CHANNEL_CAPACITY);
+        channels.insert(model.path.clone(), sender);
+        receiver
+    }
+}
+
+/// Subscribe to the records of `model` matched by a findMany style `finder`.
+///
+/// The first event is named `init` and carries the currently matched records.
+/// After that, inserted and updated objects are re-checked against the finder
+/// and the model's read permission on behalf of the request, and delivered as
+/// `insert`, `update` or `delete` events. A record which stops matching is
+/// delivered as `delete` with its identifier. When the subscriber falls
+/// behind and changes are dropped, the finder runs again and a new `init`
+/// event replaces the matched records.
+pub async fn subscribe(model: &'static Model, finder: Value, req_ctx: request::Ctx) -> Result<impl Stream<Item = Result<Event>> + Send> {
+    let receiver = changes(model);
+    let mut state = SubscriptionState { model, finder, req_ctx, receiver: Some(receiver), matched: HashSet::new() };
+    let initial = state.snapshot().await?;
+    let updates = stream::unfold(state, |mut state| async move {
+        loop {
+            let change = match state.receiver.as_mut().unwrap().recv().await {
+                Ok(change) => change,
+                Err(RecvError::Lagged(_)) => {
+                    let event = state.snapshot().await;
+                    return Some((event, state));
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            match state.event_for_change(change).await {
+                Ok(Some(event)) => return Some((Ok(event), state)),
+                Ok(None) => continue,
+                Err(error) => return Some((Err(error), state)),
+            }
+        }
+    });
+    Ok(stream::iter(vec![Ok(initial)]).chain(updates))
+}
+
+struct SubscriptionState {
+    model: &'static Model,
+    finder: Value,
+    req_ctx: request::Ctx,
+    receiver: Option<Receiver<Change>>,
+    matched: HashSet<String>,
+}
+
+impl Drop for SubscriptionState {
+
+    fn drop(&mut self) {
+        drop(self.receiver.take());
+        prune(&self.model.path);
+    }
+}
+
+impl SubscriptionState {
+
+    /// Run the finder and replace the matched records with its result.
+    async fn snapshot(&mut self) -> Result<Event> {
+        let objects = self.req_ctx.transaction_ctx().find_many_internal(self.model, &self.finder, false, FIND_MANY_HANDLER, Some(self.req_ctx.clone()), path![]).await?;
+        let mut matched = HashSet::new();
+        let mut records = vec![];
+        for object in objects {
+            matched.insert(identifier_key(&object));
+            records.push(object.to_teon().await?);
+        }
+        self.matched = matched;
+        Ok(Event::new(Value::Array(records)).with_event("init"))
+    }
+
+    async fn event_for_change(&mut self, change: Change) -> Result<Option<Event>> {
+        let key = identifier_key(&change.object);
+        let was_matched = self.matched.contains(&key);
+        if change.kind == ChangeKind::Delete {
+            return Ok(if self.matched.remove(&key) {
+                Some(Event::new(change.object.identifier()).with_event(ChangeKind::Delete.event_name()))
+            } else {
+                None
+            });
+        }
+        let finder = self.finder_for_object(&change.object);
+        let found = self.req_ctx.transaction_ctx().find_first_internal(self.model, &finder, false, FIND_FIRST_HANDLER, Some(self.req_ctx.clone()), path![]).await?;
+        let record = match found {
+            Some(object) => Some(object.to_teon().await?),
+            None => None,
+        };
+        Ok(match record {
+            Some(record) => {
+                self.matched.insert(key);
+                let kind = if was_matched { ChangeKind::Update } else { ChangeKind::Insert };
+                Some(Event::new(record).with_event(kind.event_name()))
+            }
+            None => if self.matched.remove(&key) {
+                Some(Event::new(change.object.identifier()).with_event(ChangeKind::Delete.event_name()))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn finder_for_object(&self, object: &Object) -> Value {
+        let identifier = object.identifier();
+        let r#where = match self.finder.get("where") {
+            Some(r#where) => Value::Dictionary(indexmap! {
+                "AND".to_owned() => Value::Array(vec![r#where.clone(), identifier]),
+            }),
+            None => identifier,
+        };
+        let mut finder = indexmap! { "where".to_owned() => r#where };
+        for key in ["select", "include"] {
+            if let Some(value) = self.finder.get(key) {
+                finder.insert(key.to_owned(), value.clone());
+            }
+        }
+        Value::Dictionary(finder)
+    }
+}
+
+fn identifier_key(object: &Object) -> String {
+    let identifier: Result<JsonValue> = object.identifier().try_into();
+    identifier.map_or(String::new(), |i| i.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(name: &str) -> Model {
+        let mut model = Model::new();
+        model.path = vec![name.to_owned()];
+        model
+    }
+
+    fn has_channel(model: &Model) -> bool {
+        CHANNELS.lock().unwrap().contains_key(&model.path)
+    }
+
+    #[test]
+    fn receivers_of_a_model_share_one_channel() {
+        let model = model("SharedChannel");
+        let first = changes(&model);
+        let second = changes(&model);
+        assert_eq!(CHANNELS.lock().unwrap().get(&model.path).unwrap().receiver_count(), 2);
+        drop(first);
+        drop(second);
+        prune(&model.path);
+    }
+
+    #[test]
+    fn channel_is_removed_with_its_last_receiver() {
+        let model = model("PrunedChannel");
+        let first = changes(&model);
+        let second = changes(&model);
+        drop(first);
+        prune(&model.path);
+        assert!(has_channel(&model));
+        drop(second);
+        prune(&model.path);
+        assert!(!has_channel(&model));
+    }
+
+    #[test]
+    fn change_kinds_are_named_like_events() {
+        assert_eq!(ChangeKind::Insert.event_name(), "insert");
+        assert_eq!(ChangeKind::Update.event_name(), "update");
+        assert_eq!(ChangeKind::Delete.event_name(), "delete");
+    }
+}
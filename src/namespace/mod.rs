@@ -12,6 +12,11 @@ use crate::config::debug::Debug;
 use crate::config::entity::Entity;
 use crate::config::server::Server;
 use crate::connection::connection::Connection;
+use crate::storage::FileStorage;
+use crate::cookie::CookieKeys;
+use crate::response::{ErrorFormatter, Response};
+use crate::i18n::MessageCatalog;
+use crate::admin::language::Language;
 use teo_result::Error;
 use crate::handler;
 use crate::interface::Interface;
@@ -61,6 +66,7 @@ pub struct Namespace {
     pub interface_decorators: BTreeMap<String, interface::Decorator>,
     pub interface_field_decorators: BTreeMap<String, interface::field::Decorator>,
     pub handler_decorators: BTreeMap<String, handler::Decorator>,
+    pub handler_group_decorators: BTreeMap<String, handler::group::Decorator>,
     pub pipeline_items: BTreeMap<String, pipeline::Item>,
     pub middlewares: BTreeMap<String, middleware::Definition>,
     pub handlers: BTreeMap<String, Handler>,
@@ -78,10 +84,21 @@ pub struct Namespace {
     pub connector_reference: Option<Vec<String>>,
     #[serde(skip)]
     pub connection: Option<Arc<dyn Connection>>,
+    #[serde(skip)]
+    pub storages: BTreeMap<String, Arc<dyn FileStorage>>,
+    #[serde(skip)]
+    pub cookie_keys: Option<CookieKeys>,
+    #[educe(Debug(ignore))] #[serde(skip)]
+    pub error_formatter: Option<Arc<dyn ErrorFormatter>>,
+    #[serde(skip)]
+    pub messages: MessageCatalog,
     #[educe(Debug(ignore))] #[serde(skip)]
     pub middleware_stack: &'static dyn Middleware,
     #[educe(Debug(ignore))] #[serde(skip)]
     pub handler_map: handler::Map,
+    /// Built when the schema loads if the GraphQL endpoint is included.
+    #[educe(Debug(ignore))] #[serde(skip)]
+    pub graphql_schema: Option<async_graphql::dynamic::Schema>,
     pub model_opposite_relations_map: BTreeMap<Vec<String>, Vec<(Vec<String>, String)>> // model error_ext, relation name
 }
 
@@ -89,7 +106,9 @@ impl Namespace {
 
     /// Create a main namespace
     pub fn main() -> Self {
-        Self::new(vec![])
+        let mut namespace = Self::new(vec![]);
+        namespace.messages = MessageCatalog::builtin();
+        namespace
     }
 
     fn new(path: Vec<String>) -> Self {
@@ -109,6 +128,7 @@ impl Namespace {
             interface_decorators: btreemap! {},
             interface_field_decorators: btreemap! {},
             handler_decorators: btreemap! {},
+            handler_group_decorators: btreemap! {},
             pipeline_items: btreemap!{},
             middlewares: btreemap! {},
             model_handler_groups: btreemap! {},
@@ -125,8 +145,13 @@ impl Namespace {
             database: None,
             connector_reference: None,
             connection: None,
+            storages: btreemap!{},
+            cookie_keys: None,
+            error_formatter: None,
+            messages: MessageCatalog::new(),
             middleware_stack: empty_middleware(),
             handler_map: handler::Map::new(),
+            graphql_schema: None,
             model_opposite_relations_map: btreemap! {},
         }
     }
@@ -200,6 +225,18 @@ impl Namespace {
         self.model_decorators.insert(name.to_owned(), model::Decorator { path: next_path(&self.path, name), call: Arc::new(call) });
     }
 
+    pub fn set_error_formatter(&mut self, formatter: impl ErrorFormatter + 'static) {
+        self.error_formatter = Some(Arc::new(formatter));
+    }
+
+    pub fn define_message(&mut self, id: &str, language: Language, text: impl Into<String>) {
+        self.messages.define(id, &language, text);
+    }
+
+    pub fn define_storage(&mut self, name: &str, storage: impl FileStorage + 'static) {
+        self.storages.insert(name.to_owned(), Arc::new(storage));
+    }
+
     pub fn define_model_field_decorator(&mut self, name: &str, call: impl Fn(Arguments, &mut Field) -> Result<()> + 'static) {
         self.model_field_decorators.insert(name.to_owned(), model::field::Decorator { path: next_path(&self.path, name), call: Arc::new(call) });
     }
@@ -228,6 +265,10 @@ impl Namespace {
         self.handler_decorators.insert(name.to_owned(), handler::Decorator { path: next_path(&self.path, name), call: Arc::new(call) });
     }
 
+    pub fn define_handler_group_decorator(&mut self, name: &str, call: impl Fn(Arguments, &mut handler::Group) -> Result<()> + 'static) {
+        self.handler_group_decorators.insert(name.to_owned(), handler::group::Decorator { path: next_path(&self.path, name), call: Arc::new(call) });
+    }
+
     pub fn define_pipeline_item<T>(&mut self, name: &str, call: T) where T: pipeline::item::Call + 'static {
         self.pipeline_items.insert(name.to_owned(), pipeline::Item {
             path: next_path(&self.path, name),
@@ -360,10 +401,7 @@ impl Namespace {
     }
 
     pub fn define_model_handler_group<T>(&mut self, name: &str, builder: T) where T: Fn(&mut handler::Group) {
-        let handler_group = handler::Group {
-            path: next_path(&self.path, name),
-            handlers: btreemap!{},
-        };
+        let handler_group = handler::Group::new(next_path(&self.path, name));
         self.model_handler_groups.insert(name.to_owned(), handler_group);
         builder(self.model_handler_groups.get_mut(name).unwrap());
     }
@@ -378,9 +416,12 @@ impl Namespace {
             format: HandlerInputFormat::Json,
             path: next_path(&self.path, name),
             ignore_prefix: false,
+            timeout: None,
             method: Method::Post,
             interface: None,
             url: None,
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
             call: Box::leak(Box::new(|ctx: request::Ctx| async {
                 wrapped_call.call(ctx).await
             })),
@@ -398,9 +439,12 @@ impl Namespace {
             format: HandlerInputFormat::Json,
             path: next_path(&self.path, name),
             ignore_prefix: false,
+            timeout: None,
             method: Method::Post,
             interface: None,
             url: None,
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
             call: Box::leak(Box::new(|ctx: request::Ctx| async {
                 wrapped_call.call(ctx).await
             })),
@@ -409,10 +453,7 @@ impl Namespace {
     }
 
     pub fn define_handler_group<T>(&mut self, name: &str, builder: T) where T: Fn(&mut handler::Group) {
-        let handler_group = handler::Group {
-            path: next_path(&self.path, name),
-            handlers: btreemap!{},
-        };
+        let handler_group = handler::Group::new(next_path(&self.path, name));
         self.handler_groups.insert(name.to_owned(), handler_group);
         builder(self.handler_groups.get_mut(name).unwrap());
     }
@@ -518,6 +559,16 @@ impl Namespace {
         }
     }
 
+    pub fn handler_group_decorator_at_path(&self, path: &Vec<&str>) -> Option<&handler::group::Decorator> {
+        let decorator_name = path.last().unwrap().deref();
+        let namespace_path: Vec<&str> = path.into_iter().rev().skip(1).rev().map(|i| *i).collect();
+        if let Some(ns) = self.namespace_at_path(&namespace_path) {
+            ns.handler_group_decorators.get(decorator_name)
+        } else {
+            None
+        }
+    }
+
     pub fn pipeline_item_at_path(&self, path: &Vec<&str>) -> Option<&pipeline::Item> {
         let pipeline_item_name = path.last().unwrap().deref();
         let namespace_path: Vec<&str> = path.into_iter().rev().skip(1).rev().map(|i| *i).collect();
@@ -578,6 +629,66 @@ impl Namespace {
         }
     }
 
+    pub fn storage_at_path(&self, path: &Vec<&str>) -> Option<&Arc<dyn FileStorage>> {
+        let storage_name = path.last().unwrap().deref();
+        let namespace_path: Vec<&str> = path.into_iter().rev().skip(1).rev().map(|i| *i).collect();
+        if let Some(ns) = self.namespace_at_path(&namespace_path) {
+            ns.storages.get(storage_name)
+        } else {
+            None
+        }
+    }
+
+    /// The formatter of the namespace at `namespace_path`, or of its nearest
+    /// ancestor which has one.
+    pub fn error_formatter_at_path(&self, namespace_path: &Vec<&str>) -> Option<&Arc<dyn ErrorFormatter>> {
+        let mut result = self.error_formatter.as_ref();
+        let mut current = self;
+        for item in namespace_path {
+            match current.namespaces.get(*item) {
+                Some(namespace) => current = namespace,
+                None => break,
+            }
+            if let Some(formatter) = current.error_formatter.as_ref() {
+                result = Some(formatter);
+            }
+        }
+        result
+    }
+
+    /// Translate `error` with the catalog of the namespace at
+    /// `namespace_path`, falling back to the catalogs of its ancestors.
+    pub fn localize_error_at_path(&self, namespace_path: &Vec<&str>, error: &Error, language: &Language) -> Error {
+        let mut catalogs = vec![&self.messages];
+        let mut current = self;
+        for item in namespace_path {
+            match current.namespaces.get(*item) {
+                Some(namespace) => current = namespace,
+                None => break,
+            }
+            catalogs.push(&current.messages);
+        }
+        MessageCatalog::localize_error_in(&catalogs, error, language)
+    }
+
+    /// Build the response for an error raised by a handler in the namespace
+    /// at `namespace_path`.
+    /// The error is localized first when the request's `Accept-Language`
+    /// prefers a supported language.
+    pub fn error_response(&self, namespace_path: &Vec<&str>, error: &Error, request: Option<&request::Request>) -> Response {
+        let language = request.and_then(|r| r.headers().get("accept-language")).and_then(Language::from_accept_language);
+        let localized = language.map(|language| self.localize_error_at_path(namespace_path, error, &language));
+        let error = localized.as_ref().unwrap_or(error);
+        match self.error_formatter_at_path(namespace_path) {
+            Some(formatter) => formatter.format(error, request),
+            None => {
+                let res = Response::teon(error.into());
+                res.set_code(error.code);
+                res
+            }
+        }
+    }
+
     pub fn handler_template_at_path(&self, path: &Vec<&str>) -> Option<&Handler> {
         let handler_name = path.last().unwrap().deref();
         if path.len() == 1 {
@@ -629,6 +740,37 @@ impl Namespace {
         dest_namespace.handler_templates.insert(handler_name.to_string(), handler);
     }
 
+    pub fn handler_group_at_path(&self, path: &Vec<&str>) -> Option<&handler::Group> {
+        let group_name = path.last()?.deref();
+        let namespace_path: Vec<&str> = path.into_iter().rev().skip(1).rev().map(|i| *i).collect();
+        let dest_namespace = self.namespace_at_path(&namespace_path)?;
+        dest_namespace.handler_groups.get(group_name).or_else(|| dest_namespace.model_handler_groups.get(group_name))
+    }
+
+    pub fn handler_group_mut_at_path(&mut self, path: &Vec<&str>) -> Option<&mut handler::Group> {
+        let group_name = path.last().unwrap().deref();
+        let namespace_path: Vec<&str> = path.into_iter().rev().skip(1).rev().map(|i| *i).collect();
+        let dest_namespace = self.namespace_mut_at_path(&namespace_path)?;
+        if dest_namespace.handler_groups.contains_key(group_name) {
+            dest_namespace.handler_groups.get_mut(group_name)
+        } else {
+            dest_namespace.model_handler_groups.get_mut(group_name)
+        }
+    }
+
+    /// The custom handlers of this namespace, of its handler groups and of
+    /// its child namespaces.
+    pub fn collect_handlers(&self) -> Vec<&Handler> {
+        let mut result: Vec<&Handler> = self.handlers.values().collect();
+        for group in self.handler_groups.values().chain(self.model_handler_groups.values()) {
+            result.extend(group.handlers.values());
+        }
+        for child in self.namespaces.values() {
+            result.extend(child.collect_handlers());
+        }
+        result
+    }
+
     pub fn replace_handler_at_path(&mut self, path: &Vec<&str>, handler: Handler, inside_group: bool) {
         let handler_name = path.last().unwrap().deref();
         let group_name = if inside_group {
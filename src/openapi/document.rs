@@ -0,0 +1,244 @@
+use indexmap::IndexMap;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use teo_parser::ast::handler::HandlerInputFormat;
+use teo_parser::r#type::Type;
+use crate::action::Action;
+use crate::action::action::*;
+use crate::handler::Handler;
+use crate::handler::handler::Method;
+use crate::handler::input::builtin::builtin_action_input_type;
+use crate::handler::map::handler_url;
+use crate::model::Model;
+use crate::namespace::Namespace;
+use crate::openapi::schema::SchemaGenerator;
+use crate::traits::named::Named;
+
+#[derive(Debug, Clone)]
+pub struct OpenApiOptions {
+    pub title: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub servers: Vec<String>,
+}
+
+impl Default for OpenApiOptions {
+
+    fn default() -> Self {
+        Self {
+            title: "Teo API".to_owned(),
+            version: "1.0.0".to_owned(),
+            description: None,
+            servers: vec![],
+        }
+    }
+}
+
+/// Generate an OpenAPI 3.1 document describing the builtin model actions
+/// and the custom handlers of `main_namespace`.
+pub fn generate_openapi(main_namespace: &Namespace, options: &OpenApiOptions) -> JsonValue {
+    let mut generator = SchemaGenerator::new(main_namespace, "#/components/schemas/");
+    let mut paths: IndexMap<String, JsonMap<String, JsonValue>> = IndexMap::new();
+    for model in collect_models(main_namespace) {
+        let namespace_path: Vec<&str> = model.namespace_path();
+        for action in model.builtin_handlers.iter() {
+            let url = handler_url(&namespace_path, Some(model.name()), action.as_handler_str(), Some(action.as_handler_str()), false);
+            let operation = builtin_operation(&mut generator, model, *action);
+            paths.entry(url).or_default().insert("post".to_owned(), operation);
+        }
+    }
+    for handler in main_namespace.collect_handlers() {
+        let handler_path: Vec<&str> = handler.path.iter().map(|s| s.as_str()).collect();
+        let (url, path_args) = openapi_url(&handler_route_url(handler));
+        let operation = handler_operation(&mut generator, main_namespace, handler, &handler_path, &path_args);
+        paths.entry(url).or_default().insert(handler.method.capitalized_name().to_lowercase(), operation);
+    }
+    generator.definitions.insert("Error".to_owned(), json!({
+        "type": "object",
+        "properties": {
+            "type": {"type": "string"},
+            "message": {"type": "string"},
+            "errors": {"type": "object", "additionalProperties": {"type": "string"}},
+        },
+        "required": ["type", "message"],
+    }));
+    let mut info = json!({"title": options.title, "version": options.version});
+    if let Some(description) = &options.description {
+        info.as_object_mut().unwrap().insert("description".to_owned(), json!(description));
+    }
+    let mut document = json!({
+        "openapi": "3.1.0",
+        "jsonSchemaDialect": "https://json-schema.org/draft/2020-12/schema",
+        "info": info,
+        "paths": paths,
+        "components": {
+            "schemas": generator.definitions,
+        },
+    });
+    if !options.servers.is_empty() {
+        document.as_object_mut().unwrap().insert("servers".to_owned(), JsonValue::Array(options.servers.iter().map(|url| json!({"url": url})).collect()));
+    }
+    document
+}
+
+fn collect_models(namespace: &Namespace) -> Vec<&Model> {
+    let mut result: Vec<&Model> = namespace.models.values().collect();
+    for child in namespace.namespaces.values() {
+        result.extend(collect_models(child));
+    }
+    result
+}
+
+/// The url `handler` is served at. POST handlers without a custom url are
+/// found by the default `/namespace/group/name` match, the others are
+/// routed by the url they're recorded with in the handler map.
+fn handler_route_url(handler: &Handler) -> String {
+    let namespace_path: Vec<&str> = handler.namespace_path.iter().map(|s| s.as_str()).collect();
+    let group_name = (handler.path.len() > handler.namespace_path.len() + 1).then(|| handler.path[handler.path.len() - 2].as_str());
+    let custom_url = if handler.method == Method::Post && handler.url.is_none() {
+        Some(handler.name())
+    } else {
+        handler.url.as_deref()
+    };
+    handler_url(&namespace_path, group_name, handler.name(), custom_url, handler.ignore_prefix)
+}
+
+/// Convert `:name` and `*name` url segments into OpenAPI path templates.
+fn openapi_url(url: &str) -> (String, Vec<String>) {
+    let mut args = vec![];
+    let segments: Vec<String> = url.split('/').map(|segment| {
+        match segment.strip_prefix(':').or_else(|| segment.strip_prefix('*')) {
+            Some(name) if !name.is_empty() => {
+                args.push(name.to_owned());
+                format!("{{{}}}", name)
+            }
+            _ => segment.to_owned(),
+        }
+    }).collect();
+    (segments.join("/"), args)
+}
+
+fn builtin_operation(generator: &mut SchemaGenerator, model: &Model, action: Action) -> JsonMap<String, JsonValue> {
+    let mut operation = JsonMap::new();
+    operation.insert("operationId".to_owned(), json!(format!("{}.{}", model.path.join("."), action.as_handler_str())));
+    operation.insert("tags".to_owned(), json!([model.path.join(".")]));
+    if let Some(input) = builtin_action_input_type(model, action) {
+        operation.insert("requestBody".to_owned(), json!({
+            "required": true,
+            "content": {"application/json": {"schema": generator.schema_for_type(input)}},
+        }));
+    }
+    let record = generator.model_reference(model);
+    let data = match action {
+        FIND_MANY_HANDLER | CREATE_MANY_HANDLER | UPDATE_MANY_HANDLER | COPY_MANY_HANDLER | DELETE_MANY_HANDLER => json!({
+            "type": "object",
+            "properties": {
+                "data": {"type": "array", "items": record},
+                "meta": {"type": "object", "properties": {"count": {"type": "integer"}}},
+            },
+            "required": ["data"],
+        }),
+        COUNT_HANDLER => json!({"type": "object", "properties": {"data": {"type": "integer"}}, "required": ["data"]}),
+        AGGREGATE_HANDLER | GROUP_BY_HANDLER => json!({"type": "object", "properties": {"data": {}}, "required": ["data"]}),
+        _ => json!({"type": "object", "properties": {"data": record}, "required": ["data"]}),
+    };
+    operation.insert("responses".to_owned(), responses(data, "application/json"));
+    operation
+}
+
+fn handler_operation(generator: &mut SchemaGenerator, main_namespace: &Namespace, handler: &Handler, handler_path: &Vec<&str>, path_args: &Vec<String>) -> JsonMap<String, JsonValue> {
+    let mut operation = JsonMap::new();
+    operation.insert("operationId".to_owned(), json!(handler_path.join(".")));
+    if handler_path.len() > 1 {
+        operation.insert("tags".to_owned(), json!([handler_path[..handler_path.len() - 1].join(".")]));
+    }
+    let mut parameters = vec![];
+    let interface = handler.custom_url_args_path().and_then(|path| main_namespace.interface_at_path(&path.iter().map(|s| s.as_str()).collect()));
+    for name in path_args {
+        let schema = match interface.and_then(|i| i.shape.get(name)) {
+            Some(t) => generator.schema_for_type(t),
+            None => json!({"type": "string"}),
+        };
+        parameters.push(json!({"name": name, "in": "path", "required": true, "schema": schema}));
+    }
+    if handler.has_body_input() {
+        if !is_untyped(&handler.input_type) {
+            let content_type = match handler.format {
+                HandlerInputFormat::Form => "multipart/form-data",
+                _ => "application/json",
+            };
+            operation.insert("requestBody".to_owned(), json!({
+                "required": !handler.input_type.is_optional(),
+                "content": content(content_type, generator.schema_for_type(&handler.input_type)),
+            }));
+        }
+    } else if !is_untyped(&handler.input_type) {
+        let schema = generator.schema_for_type(&handler.input_type);
+        parameters.push(json!({
+            "name": "query",
+            "in": "query",
+            "style": "deepObject",
+            "explode": true,
+            "schema": schema,
+        }));
+    }
+    if !parameters.is_empty() {
+        operation.insert("parameters".to_owned(), JsonValue::Array(parameters));
+    }
+    let (schema, content_type) = if handler.nonapi {
+        (json!({}), "*/*")
+    } else if is_untyped(&handler.output_type) {
+        (json!({}), "application/json")
+    } else {
+        (generator.schema_for_type(&handler.output_type), "application/json")
+    };
+    operation.insert("responses".to_owned(), responses(schema, content_type));
+    operation
+}
+
+fn responses(schema: JsonValue, content_type: &str) -> JsonValue {
+    json!({
+        "200": {
+            "description": "OK",
+            "content": content(content_type, schema),
+        },
+        "default": {
+            "description": "Error",
+            "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Error"}}},
+        },
+    })
+}
+
+fn content(content_type: &str, schema: JsonValue) -> JsonValue {
+    let mut content = JsonMap::new();
+    content.insert(content_type.to_owned(), json!({"schema": schema}));
+    JsonValue::Object(content)
+}
+
+fn is_untyped(t: &Type) -> bool {
+    matches!(t, Type::Undetermined | Type::Ignored | Type::Any)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handler::handler::Method;
+    use crate::namespace::Namespace;
+    use crate::response::Response;
+    use super::{generate_openapi, OpenApiOptions};
+
+    #[test]
+    fn documents_handlers_of_namespaces_and_groups() {
+        let mut namespace = Namespace::main();
+        namespace.define_handler("ping", || async { Ok(Response::empty()) });
+        namespace.define_handler("status", || async { Ok(Response::empty()) });
+        namespace.handlers.get_mut("status").unwrap().method = Method::Get;
+        namespace.define_handler_group("users", |group| group.define_handler("list", || async { Ok(Response::empty()) }));
+        namespace.namespace_mut_or_create_at_path(&vec!["admin"]).define_handler("stats", || async { Ok(Response::empty()) });
+        let document = generate_openapi(&namespace, &OpenApiOptions::default());
+        let paths = document["paths"].as_object().unwrap();
+        assert!(paths["/ping"]["post"].is_object());
+        assert!(paths["/status"]["get"].is_object());
+        assert!(paths["/users/list"]["post"].is_object());
+        assert!(paths["/admin/stats"]["post"].is_object());
+        assert_eq!(paths["/users/list"]["post"]["operationId"], "users.list");
+    }
+}
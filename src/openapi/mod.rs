@@ -0,0 +1,5 @@
+pub mod schema;
+pub mod document;
+
+pub use document::{generate_openapi, OpenApiOptions};
+pub use schema::{SchemaGenerator, ValueEncoding};
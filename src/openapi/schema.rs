@@ -0,0 +1,271 @@
+use indexmap::IndexMap;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use teo_parser::r#type::synthesized_enum::SynthesizedEnum;
+use teo_parser::r#type::synthesized_shape::SynthesizedShape;
+use teo_parser::r#type::synthesized_shape_reference::{SynthesizedShapeReference, SynthesizedShapeReferenceKind};
+use teo_parser::r#type::Type;
+use crate::coder::json_to_teon::{fetch_input, fetch_synthesized_enum};
+use crate::comment::Comment;
+use crate::interface::Interface;
+use crate::model::Model;
+use crate::namespace::Namespace;
+use crate::r#enum::Enum;
+
+/// How values whose types json can't tell apart are written.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueEncoding {
+    /// Plain json values, as handlers accept them.
+    Plain,
+    /// Single entry maps like `{"$int64": 1}`, as `Value` serializes.
+    Tagged,
+}
+
+/// Converts teon types into JSON Schema 2020-12 schemas.
+///
+/// Models, interfaces, enums and synthesized shapes are collected into
+/// `definitions` and referenced with `ref_prefix`, which is
+/// `#/components/schemas/` in OpenAPI documents and `#/$defs/` in standalone
+/// schemas.
+pub struct SchemaGenerator<'a> {
+    main_namespace: &'a Namespace,
+    ref_prefix: &'static str,
+    encoding: ValueEncoding,
+    pub definitions: IndexMap<String, JsonValue>,
+}
+
+impl<'a> SchemaGenerator<'a> {
+
+    pub fn new(main_namespace: &'a Namespace, ref_prefix: &'static str) -> Self {
+        Self { main_namespace, ref_prefix, encoding: ValueEncoding::Plain, definitions: IndexMap::new() }
+    }
+
+    pub fn with_encoding(mut self, encoding: ValueEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn schema_for_type(&mut self, t: &Type) -> JsonValue {
+        let main_namespace = self.main_namespace;
+        if self.encoding == ValueEncoding::Tagged {
+            if let Some(schema) = tagged_scalar_schema(t) {
+                return schema;
+            }
+        }
+        match t {
+            Type::Null => json!({"type": "null"}),
+            Type::Bool => json!({"type": "boolean"}),
+            Type::Int => json!({"type": "integer", "format": "int32"}),
+            Type::Int64 => json!({"type": "integer", "format": "int64"}),
+            Type::Float32 => json!({"type": "number", "format": "float"}),
+            Type::Float => json!({"type": "number", "format": "double"}),
+            Type::Decimal => json!({"type": "string", "format": "decimal"}),
+            Type::String => json!({"type": "string"}),
+            Type::ObjectId => object_id_schema(),
+            Type::Date => json!({"type": "string", "format": "date"}),
+            Type::DateTime => json!({"type": "string", "format": "date-time"}),
+            Type::File => json!({"type": "string", "format": "binary"}),
+            Type::Regex => json!({"type": "string", "format": "regex"}),
+            Type::Array(inner) => json!({"type": "array", "items": self.schema_for_type(inner.as_ref())}),
+            Type::Enumerable(inner) => {
+                let inner = self.schema_for_type(inner.as_ref());
+                json!({"anyOf": [inner.clone(), {"type": "array", "items": inner}]})
+            }
+            Type::Dictionary(inner) => json!({"type": "object", "additionalProperties": self.schema_for_type(inner.as_ref())}),
+            Type::Tuple(types) => {
+                let items: Vec<JsonValue> = types.iter().map(|t| self.schema_for_type(t)).collect();
+                json!({"type": "array", "prefixItems": items, "items": false})
+            }
+            Type::Optional(inner) => json!({"anyOf": [self.schema_for_type(inner.as_ref()), {"type": "null"}]}),
+            Type::Union(types) => json!({"anyOf": types.iter().map(|t| self.schema_for_type(t)).collect::<Vec<JsonValue>>()}),
+            Type::EnumVariant(reference) => match main_namespace.enum_at_path(&reference.str_path()) {
+                Some(e) => self.enum_reference(e),
+                None => json!({"type": "string"}),
+            },
+            Type::SynthesizedEnum(synthesized_enum) => synthesized_enum_schema(synthesized_enum),
+            Type::SynthesizedEnumReference(reference) => synthesized_enum_schema(fetch_synthesized_enum(reference, main_namespace)),
+            Type::SynthesizedShape(shape) => self.schema_for_shape(shape, None),
+            Type::SynthesizedShapeReference(reference) => self.shape_reference(reference),
+            Type::DeclaredSynthesizedShape(reference, model_type) => {
+                let shape = model_type.as_model_object()
+                    .and_then(|m| main_namespace.model_at_path(&m.str_path()))
+                    .and_then(|m| m.cache.shape.get_declared(reference.string_path()));
+                match shape {
+                    Some(shape) => self.definition(reference.string_path().join("."), |generator| generator.schema_for_shape(shape, None)),
+                    None => json!({}),
+                }
+            }
+            Type::InterfaceObject(reference, generics) => match main_namespace.interface_at_path(&reference.str_path()) {
+                Some(interface) => if generics.is_empty() {
+                    self.interface_reference(interface)
+                } else {
+                    self.schema_for_shape(&interface.shape_from_generics(generics), Some(interface))
+                },
+                None => json!({"type": "object"}),
+            },
+            Type::ModelObject(reference) => match main_namespace.model_at_path(&reference.str_path()) {
+                Some(model) => self.model_reference(model),
+                None => json!({"type": "object"}),
+            },
+            _ => json!({}),
+        }
+    }
+
+    pub fn schema_for_shape(&mut self, shape: &SynthesizedShape, interface: Option<&Interface>) -> JsonValue {
+        let mut properties = JsonMap::new();
+        let mut required = vec![];
+        for (key, t) in shape.iter() {
+            let mut schema = self.schema_for_type(t);
+            if let Some(field) = interface.and_then(|i| i.fields.get(key)) {
+                describe(&mut schema, field.comment.as_ref());
+            }
+            properties.insert(key.to_owned(), schema);
+            if !t.is_optional() {
+                required.push(JsonValue::String(key.to_owned()));
+            }
+        }
+        let mut schema = json!({"type": "object", "properties": properties, "additionalProperties": false});
+        if !required.is_empty() {
+            schema.as_object_mut().unwrap().insert("required".to_owned(), JsonValue::Array(required));
+        }
+        schema
+    }
+
+    pub fn model_reference(&mut self, model: &Model) -> JsonValue {
+        self.definition(model.path.join("."), |generator| generator.model_schema(model))
+    }
+
+    pub fn interface_reference(&mut self, interface: &Interface) -> JsonValue {
+        self.definition(interface.path.join("."), |generator| {
+            let mut schema = generator.schema_for_shape(&interface.shape, Some(interface));
+            describe(&mut schema, interface.comment.as_ref());
+            schema
+        })
+    }
+
+    pub fn enum_reference(&mut self, e: &Enum) -> JsonValue {
+        self.definition(e.path.join("."), |_| {
+            let names: Vec<&str> = e.members().iter().map(|m| m.name.as_str()).collect();
+            let mut schema = if e.option {
+                json!({"type": "array", "items": {"type": "string", "enum": names}})
+            } else {
+                json!({"type": "string", "enum": names})
+            };
+            describe(&mut schema, e.comment.as_ref());
+            schema
+        })
+    }
+
+    fn shape_reference(&mut self, reference: &SynthesizedShapeReference) -> JsonValue {
+        let main_namespace = self.main_namespace;
+        let owner = reference.owner.as_model_object().map(|m| m.string_path().join(".")).unwrap_or_default();
+        let name = format!("{}.{}", owner, shape_name(reference.kind, reference.without.as_ref()));
+        let t = fetch_input(reference, main_namespace);
+        self.definition(name, |generator| generator.schema_for_type(t))
+    }
+
+    fn model_schema(&mut self, model: &Model) -> JsonValue {
+        let main_namespace = self.main_namespace;
+        let mut properties = JsonMap::new();
+        let mut required = vec![];
+        for field in model.fields() {
+            if field.read.is_no_read() {
+                continue
+            }
+            let mut schema = self.schema_for_type(&field.r#type);
+            describe(&mut schema, field.comment.as_ref());
+            properties.insert(field.name.clone(), schema);
+            if field.optionality.is_required() && !field.output_omissible {
+                required.push(JsonValue::String(field.name.clone()));
+            }
+        }
+        for relation in model.relations() {
+            let mut schema = match main_namespace.model_at_path(&relation.model.iter().map(|s| s.as_str()).collect()) {
+                Some(related) => {
+                    let reference = self.model_reference(related);
+                    if relation.is_vec { json!({"type": "array", "items": reference}) } else { reference }
+                }
+                None => json!({}),
+            };
+            describe(&mut schema, relation.comment.as_ref());
+            properties.insert(relation.name.clone(), schema);
+        }
+        for property in model.properties() {
+            let mut schema = self.schema_for_type(&property.r#type);
+            describe(&mut schema, property.comment.as_ref());
+            properties.insert(property.name.clone(), schema);
+        }
+        let mut schema = json!({"type": "object", "properties": properties});
+        if !required.is_empty() {
+            schema.as_object_mut().unwrap().insert("required".to_owned(), JsonValue::Array(required));
+        }
+        describe(&mut schema, model.comment.as_ref());
+        schema
+    }
+
+    /// Reference the definition `name`, building it on first use. The name is
+    /// reserved before building so recursive types terminate.
+    fn definition<F>(&mut self, name: String, build: F) -> JsonValue where F: FnOnce(&mut Self) -> JsonValue {
+        if !self.definitions.contains_key(&name) {
+            self.definitions.insert(name.clone(), JsonValue::Null);
+            let schema = build(self);
+            self.definitions.insert(name.clone(), schema);
+        }
+        json!({"$ref": format!("{}{}", self.ref_prefix, name)})
+    }
+}
+
+/// The name of a model's synthesized shape, like `CreateArgs` or
+/// `CreateInputWithoutPosts`.
+pub fn shape_name(kind: SynthesizedShapeReferenceKind, without: Option<&String>) -> String {
+    match without {
+        Some(without) => format!("{:?}Without{}", kind, without),
+        None => format!("{:?}", kind),
+    }
+}
+
+fn object_id_schema() -> JsonValue {
+    json!({"type": "string", "pattern": "^[0-9a-fA-F]{24}$"})
+}
+
+/// The schemas of the markers written by `value::serialize`.
+fn tagged_scalar_schema(t: &Type) -> Option<JsonValue> {
+    let (tag, inner) = match t {
+        Type::Int => ("$int", json!({"type": "integer", "format": "int32"})),
+        Type::Int64 => ("$int64", json!({"type": "integer", "format": "int64"})),
+        Type::Float32 => ("$float32", json!({"type": "number", "format": "float"})),
+        Type::Float => ("$float", json!({"type": "number", "format": "double"})),
+        Type::Decimal => ("$decimal", json!({"type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?$"})),
+        Type::ObjectId => ("$objectId", object_id_schema()),
+        Type::Date => ("$date", json!({"type": "string", "format": "date"})),
+        Type::DateTime => ("$datetime", json!({"type": "string", "format": "date-time"})),
+        Type::File => ("$file", json!({
+            "type": "object",
+            "properties": {
+                "filepath": {"type": "string"},
+                "contentType": {"type": ["string", "null"]},
+                "filename": {"type": "string"},
+                "filenameExt": {"type": ["string", "null"]},
+            },
+        })),
+        _ => None?,
+    };
+    let mut properties = JsonMap::new();
+    properties.insert(tag.to_owned(), inner);
+    Some(json!({"type": "object", "properties": properties, "required": [tag], "additionalProperties": false}))
+}
+
+fn synthesized_enum_schema(synthesized_enum: &SynthesizedEnum) -> JsonValue {
+    json!({"type": "string", "enum": synthesized_enum.keys})
+}
+
+fn describe(schema: &mut JsonValue, comment: Option<&Comment>) {
+    let (Some(comment), Some(object)) = (comment, schema.as_object_mut()) else {
+        return
+    };
+    if let Some(name) = &comment.name {
+        object.insert("title".to_owned(), JsonValue::String(name.clone()));
+    }
+    if let Some(desc) = &comment.desc {
+        object.insert("description".to_owned(), JsonValue::String(desc.clone()));
+    }
+}
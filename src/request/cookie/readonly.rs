@@ -1,5 +1,6 @@
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
+use crate::utils::percent_decode;
 
 #[derive(Clone)]
 pub struct Cookie {
@@ -8,32 +9,38 @@ pub struct Cookie {
 
 impl Cookie {
 
-    fn name(&self) -> &str {
+    pub fn name(&self) -> &str {
         self.inner.name()
     }
 
-    fn path(&self) -> Option<&str> {
+    pub fn path(&self) -> Option<&str> {
         self.inner.path()
     }
 
-    fn value(&self) -> &str {
+    pub fn value(&self) -> &str {
         self.inner.value()
     }
 
-    fn expires_datetime(&self) -> Option<&DateTime<Utc>> {
+    pub fn expires_datetime(&self) -> Option<&DateTime<Utc>> {
         self.inner.expires_datetime()
     }
 
-    fn expires_session(&self) -> bool {
+    pub fn expires_session(&self) -> bool {
         self.inner.expires_session()
     }
-    fn secure(&self) -> Option<bool> {
+
+    pub fn secure(&self) -> Option<bool> {
         self.inner.secure()
     }
 
-    fn max_age(&self) -> Option<f64> {
+    pub fn max_age(&self) -> Option<f64> {
         self.inner.max_age()
     }
+
+    /// The value with percent encoded bytes decoded.
+    pub fn decoded_value(&self) -> String {
+        percent_decode(self.value(), false)
+    }
 }
 
 pub mod r#trait {
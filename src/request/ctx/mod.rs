@@ -1,13 +1,18 @@
 pub mod extract;
 
 use std::cell::{Ref, RefCell, RefMut};
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Instant;
 use crate::value::Value;
 use crate::request::Request;
 use crate::connection::transaction;
 use crate::handler::r#match::HandlerMatch;
 use crate::namespace::Namespace;
 use crate::request::ctx::extract::ExtractFromRequestCtx;
+use teo_result::{Error, Result};
+use crate::cookie::CookieKeys;
+use crate::admin::language::Language;
 use super::local::Data;
 
 #[derive(Debug, Clone)]
@@ -24,7 +29,8 @@ struct CtxInner {
     body: Arc<Value>,
     transaction_ctx: transaction::Ctx,
     handler_match: HandlerMatch,
-    data: RefCell<Data>,
+    data: Arc<RefCell<Data>>,
+    joins_transaction: bool,
 }
 
 impl Ctx {
@@ -36,11 +42,51 @@ impl Ctx {
                 body,
                 transaction_ctx,
                 handler_match,
-                data: RefCell::new(Data::new())
+                data: Arc::new(RefCell::new(Data::new())),
+                joins_transaction: false,
             })
         }
     }
 
+    /// A context for running another handler within this request. The
+    /// request, transaction and request data are shared with this context.
+    pub fn derive(&self, body: Value, handler_match: HandlerMatch) -> Self {
+        self.derive_with(body, handler_match, self.inner.transaction_ctx.clone(), self.inner.joins_transaction)
+    }
+
+    /// A derived context which runs in `transaction_ctx` instead of this
+    /// context's transaction. Its `run_transaction` joins `transaction_ctx`,
+    /// the caller commits or aborts it.
+    pub fn derive_in_transaction(&self, body: Value, handler_match: HandlerMatch, transaction_ctx: transaction::Ctx) -> Self {
+        self.derive_with(body, handler_match, transaction_ctx, true)
+    }
+
+    fn derive_with(&self, body: Value, handler_match: HandlerMatch, transaction_ctx: transaction::Ctx, joins_transaction: bool) -> Self {
+        Self {
+            inner: Arc::new(CtxInner {
+                request: self.inner.request.clone(),
+                body: Arc::new(body),
+                transaction_ctx,
+                handler_match,
+                data: self.inner.data.clone(),
+                joins_transaction,
+            })
+        }
+    }
+
+    /// Run `f` in a transaction. A context from `derive_in_transaction` runs
+    /// `f` in the transaction it was given, others start a new one.
+    pub async fn run_transaction<F, Fut, C, R>(&self, f: F) -> Result<R> where
+        F: Fn(C) -> Fut,
+        C: for <'a> From<&'a transaction::Ctx>,
+        Fut: Future<Output = Result<R>> {
+        if self.inner.joins_transaction {
+            f((&self.inner.transaction_ctx).into()).await
+        } else {
+            self.inner.transaction_ctx.run_transaction(f).await
+        }
+    }
+
     pub fn request(&self) -> &Request {
         &self.inner.request
     }
@@ -68,6 +114,97 @@ impl Ctx {
     pub fn body(&self) -> &Value {
         self.inner.body.as_ref()
     }
+
+    pub fn request_id(&self) -> Option<String> {
+        self.data().get::<String>("requestId").cloned()
+    }
+
+    pub fn set_request_id(&self, request_id: impl Into<String>) {
+        self.data_mut().insert("requestId", request_id.into());
+    }
+
+    /// When the request times out. Long running handlers may check this to
+    /// stop early, they are cancelled anyway at the deadline.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.data().get::<Instant>("deadline").cloned()
+    }
+
+    /// Set the deadline unless an earlier one is set.
+    pub fn set_deadline(&self, deadline: Instant) {
+        if self.deadline().is_some_and(|current| current <= deadline) {
+            return;
+        }
+        self.data_mut().insert("deadline", deadline);
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.deadline().is_some_and(|deadline| deadline <= Instant::now())
+    }
+
+    /// The language the request's `Accept-Language` header prefers most.
+    pub fn language(&self) -> Option<Language> {
+        self.request().headers().get("accept-language").and_then(Language::from_accept_language)
+    }
+
+    /// `error` translated into the request's language.
+    pub fn localize_error(&self, error: &Error) -> Option<Error> {
+        let language = self.language()?;
+        Some(self.namespace().localize_error_at_path(&self.handler_match().path(), error, &language))
+    }
+
+    /// The value of a signed cookie. Missing cookies and cookies with invalid
+    /// signatures are both `None`.
+    pub fn signed_cookie(&self, name: &str) -> Result<Option<String>> {
+        let Some(cookie) = self.request().cookie(name)? else {
+            return Ok(None);
+        };
+        Ok(self.cookie_keys()?.verify(name, &cookie.decoded_value()))
+    }
+
+    /// The value of an encrypted cookie. Missing cookies and cookies which
+    /// cannot be decrypted are both `None`.
+    pub fn encrypted_cookie(&self, name: &str) -> Result<Option<String>> {
+        let Some(cookie) = self.request().cookie(name)? else {
+            return Ok(None);
+        };
+        Ok(self.cookie_keys()?.decrypt(name, &cookie.decoded_value()))
+    }
+
+    /// The path captures. When the handler declares a url args interface,
+    /// they are already converted against it.
+    pub fn path_args(&self) -> Value {
+        if let Some(args) = self.data().get::<Value>("pathArgs") {
+            return args.clone();
+        }
+        Value::Dictionary(self.handler_match().captures().iter().map(|(k, v)| (k.clone(), Value::String(v.clone()))).collect())
+    }
+
+    pub fn set_path_args(&self, args: Value) {
+        self.data_mut().insert("pathArgs", args);
+    }
+
+    /// The session loaded by the session middleware.
+    pub fn session(&self) -> Option<Value> {
+        self.data().get::<Value>("session").cloned()
+    }
+
+    pub fn set_session(&self, session: Value) {
+        self.data_mut().insert("session", session);
+    }
+
+    /// Move the session to a new id when the response is sent. Call this when
+    /// the privilege level changes, e.g. on sign in.
+    pub fn regenerate_session(&self) {
+        self.data_mut().insert("session:regenerate", true);
+    }
+
+    pub fn destroy_session(&self) {
+        self.data_mut().insert("session:destroy", true);
+    }
+
+    pub fn cookie_keys(&self) -> Result<&'static CookieKeys> {
+        self.namespace().cookie_keys.as_ref().ok_or_else(|| Error::internal_server_error_message("cookie keys are not configured"))
+    }
 }
 
 impl ExtractFromRequestCtx for Ctx {
@@ -75,3 +212,53 @@ impl ExtractFromRequestCtx for Ctx {
         ctx.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::connection::transaction;
+    use crate::request::stub::stub_ctx;
+    use crate::value::Value;
+    use super::Ctx;
+
+    async fn commit_counting(req_ctx: &Ctx, counter: &Arc<AtomicUsize>) {
+        let counter = counter.clone();
+        req_ctx.run_transaction(move |ctx: transaction::Ctx| {
+            let counter = counter.clone();
+            async move {
+                ctx.after_commit(move || async move { counter.fetch_add(1, Ordering::SeqCst); }).await;
+                Ok(())
+            }
+        }).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn run_transaction_commits_on_its_own() {
+        let req_ctx = stub_ctx("POST", vec![]);
+        let committed = Arc::new(AtomicUsize::new(0));
+        commit_counting(&req_ctx, &committed).await;
+        assert_eq!(committed.load(Ordering::SeqCst), 1);
+        let derived = req_ctx.derive(Value::Null, req_ctx.handler_match().clone());
+        commit_counting(&derived, &committed).await;
+        assert_eq!(committed.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn derived_in_transaction_joins_the_given_transaction() {
+        let req_ctx = stub_ctx("POST", vec![]);
+        let committed = Arc::new(AtomicUsize::new(0));
+        let counter = committed.clone();
+        req_ctx.transaction_ctx().run_transaction(move |shared: transaction::Ctx| {
+            let (req_ctx, counter) = (req_ctx.clone(), counter.clone());
+            async move {
+                let derived = req_ctx.derive_in_transaction(Value::Null, req_ctx.handler_match().clone(), shared);
+                commit_counting(&derived, &counter).await;
+                commit_counting(&derived.derive(Value::Null, req_ctx.handler_match().clone()), &counter).await;
+                assert_eq!(counter.load(Ordering::SeqCst), 0);
+                Ok(())
+            }
+        }).await.unwrap();
+        assert_eq!(committed.load(Ordering::SeqCst), 2);
+    }
+}
@@ -4,6 +4,8 @@ pub mod ctx;
 pub mod local;
 pub mod r#match;
 pub mod cookie;
+#[cfg(test)]
+pub(crate) mod stub;
 
 pub use request::Request;
 pub use ctx::Ctx;
\ No newline at end of file
@@ -40,6 +40,10 @@ impl Request {
     pub fn cookies(&self) -> Result<Vec<Cookie>> {
         self.inner.cookies()
     }
+
+    pub fn cookie(&self, name: &str) -> Result<Option<Cookie>> {
+        Ok(self.cookies()?.into_iter().find(|c| c.name() == name))
+    }
 }
 
 impl Debug for Request {
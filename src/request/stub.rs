@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use indexmap::IndexMap;
+use teo_result::Result;
+use crate::connection;
+use crate::connection::transaction;
+use crate::handler::r#match::HandlerMatch;
+use crate::namespace::Namespace;
+use chrono::{DateTime, Utc};
+use crate::request::cookie::readonly::{self as cookie, Cookie};
+use crate::request::header::readonly::{self, HeaderMap};
+use crate::request::{request, Ctx, Request};
+use crate::value::Value;
+
+/// A request for tests, without a server behind it.
+pub(crate) struct StubRequest {
+    method: String,
+    path: String,
+    headers: HeaderMap,
+}
+
+struct StubHeaderMap(BTreeMap<String, String>);
+
+impl readonly::r#trait::HeaderMap for StubHeaderMap {
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|k| k.as_str()).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.0.contains_key(key)
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(|v| v.as_str())
+    }
+}
+
+impl request::r#trait::Request for StubRequest {
+
+    fn method(&self) -> &str {
+        self.method.as_str()
+    }
+
+    fn path(&self) -> &str {
+        self.path.as_str()
+    }
+
+    fn query_string(&self) -> &str {
+        ""
+    }
+
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    fn cookies(&self) -> Result<Vec<Cookie>> {
+        let Some(header) = self.headers.get("cookie") else {
+            return Ok(vec![]);
+        };
+        Ok(header.split(';').filter_map(|pair| pair.trim().split_once('=')).map(|(name, value)| Cookie {
+            inner: Arc::new(StubCookie { name: name.to_owned(), value: value.to_owned() })
+        }).collect())
+    }
+}
+
+/// A cookie from the stub request's `cookie` header.
+struct StubCookie {
+    name: String,
+    value: String,
+}
+
+impl cookie::r#trait::Cookie for StubCookie {
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn path(&self) -> Option<&str> {
+        None
+    }
+
+    fn value(&self) -> &str {
+        self.value.as_str()
+    }
+
+    fn expires_datetime(&self) -> Option<&DateTime<Utc>> {
+        None
+    }
+
+    fn expires_session(&self) -> bool {
+        true
+    }
+
+    fn secure(&self) -> Option<bool> {
+        None
+    }
+
+    fn max_age(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// A request to `path` with `headers`.
+pub(crate) fn stub_request(method: &str, path: &str, headers: Vec<(&str, &str)>) -> Request {
+    Request::new(Arc::new(StubRequest {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        headers: HeaderMap { inner: Arc::new(StubHeaderMap(headers.into_iter().map(|(k, v)| (k.to_owned(), v.to_owned())).collect())) },
+    }))
+}
+
+/// A request ctx with `headers`, running outside of a transaction against an
+/// empty main namespace.
+pub(crate) fn stub_ctx(method: &str, headers: Vec<(&str, &str)>) -> Ctx {
+    let namespace: &'static Namespace = Box::leak(Box::new(Namespace::main()));
+    let request = stub_request(method, "/", headers);
+    let transaction_ctx = transaction::Ctx::new(connection::Ctx::from_namespace(namespace));
+    Ctx::new(request, Arc::new(Value::Null), transaction_ctx, HandlerMatch {
+        path: vec![],
+        name: "stub".to_owned(),
+        captures: IndexMap::new(),
+    })
+}
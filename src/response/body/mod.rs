@@ -1,7 +1,12 @@
+pub mod stream;
+pub mod sse;
+
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::value::Value;
+use self::stream::Stream;
+use self::sse::Sse;
 
 #[derive(Clone)]
 pub struct Body {
@@ -34,6 +39,18 @@ impl Body {
         }
     }
 
+    pub fn stream(content: Stream) -> Self {
+        Self {
+            inner: Arc::new(BodyInner::Stream(content))
+        }
+    }
+
+    pub fn sse(content: Sse) -> Self {
+        Self {
+            inner: Arc::new(BodyInner::Sse(content))
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         match self.inner.as_ref() {
             BodyInner::Empty => true,
@@ -82,6 +99,34 @@ impl Body {
             _ => None,
         }
     }
+
+    pub fn is_stream(&self) -> bool {
+        match self.inner.as_ref() {
+            BodyInner::Stream(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn as_stream(&self) -> Option<&Stream> {
+        match self.inner.as_ref() {
+            BodyInner::Stream(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn is_sse(&self) -> bool {
+        match self.inner.as_ref() {
+            BodyInner::Sse(_) => true,
+            _ => false,
+        }
+    }
+
+    pub fn as_sse(&self) -> Option<&Sse> {
+        match self.inner.as_ref() {
+            BodyInner::Sse(v) => Some(v),
+            _ => None,
+        }
+    }
 }
 
 pub enum BodyInner {
@@ -89,4 +134,6 @@ pub enum BodyInner {
     String(String),
     File(PathBuf),
     Teon(Value),
+    Stream(Stream),
+    Sse(Sse),
 }
\ No newline at end of file
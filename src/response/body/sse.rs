@@ -0,0 +1,125 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use std::time::Duration;
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
+use serde_json::{Value as JsonValue};
+use teo_result::Result;
+use crate::value::Value;
+
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: Value,
+    pub retry: Option<Duration>,
+}
+
+impl Event {
+
+    pub fn new(data: impl Into<Value>) -> Self {
+        Self {
+            id: None,
+            event: None,
+            data: data.into(),
+            retry: None,
+        }
+    }
+
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    pub fn with_retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut result = String::new();
+        if let Some(id) = &self.id {
+            result += &format!("id: {}\n", single_line(id));
+        }
+        if let Some(event) = &self.event {
+            result += &format!("event: {}\n", single_line(event));
+        }
+        if let Some(retry) = &self.retry {
+            result += &format!("retry: {}\n", retry.as_millis());
+        }
+        let json_value: JsonValue = (&self.data).try_into()?;
+        result += &format!("data: {}\n\n", json_value);
+        Ok(result.into_bytes())
+    }
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+pub struct Sse {
+    inner: Mutex<Option<BoxStream<'static, Result<Event>>>>,
+    heartbeat: Option<Duration>,
+}
+
+enum SseItem {
+    Event(Result<Event>),
+    Heartbeat,
+    End,
+}
+
+impl Sse {
+
+    pub fn new(stream: BoxStream<'static, Result<Event>>, heartbeat: Option<Duration>) -> Self {
+        Self {
+            inner: Mutex::new(Some(stream)),
+            heartbeat,
+        }
+    }
+
+    pub fn heartbeat(&self) -> Option<Duration> {
+        self.heartbeat
+    }
+
+    /// Take the event stream out. A stream can only be consumed once, later calls return `None`.
+    pub fn take(&self) -> Option<BoxStream<'static, Result<Event>>> {
+        self.inner.lock().unwrap().take()
+    }
+
+    /// Take the event stream out as encoded `text/event-stream` bytes, with heartbeat comments
+    /// interleaved to keep idle connections open.
+    pub fn take_byte_stream(&self) -> Option<BoxStream<'static, Result<Vec<u8>>>> {
+        let events = self.take()?.map(SseItem::Event).chain(stream::once(async { SseItem::End }));
+        let items = if let Some(heartbeat) = self.heartbeat {
+            let heartbeats = stream::unfold(tokio::time::interval_at(tokio::time::Instant::now() + heartbeat, heartbeat), |mut interval| async move {
+                interval.tick().await;
+                Some((SseItem::Heartbeat, interval))
+            });
+            stream::select(events, heartbeats).boxed()
+        } else {
+            events.boxed()
+        };
+        Some(items.take_while(|item| {
+            let ended = matches!(item, SseItem::End);
+            async move { !ended }
+        }).map(|item| match item {
+            SseItem::Event(event) => event?.to_bytes(),
+            SseItem::Heartbeat => Ok(b": heartbeat\n\n".to_vec()),
+            SseItem::End => unreachable!(),
+        }).boxed())
+    }
+}
+
+impl Debug for Sse {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug_struct = f.debug_struct("Sse");
+        debug_struct.field("heartbeat", &self.heartbeat);
+        debug_struct.finish()
+    }
+}
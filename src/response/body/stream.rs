@@ -0,0 +1,45 @@
+use std::fmt::{Debug, Formatter};
+use std::sync::Mutex;
+use futures_util::stream::BoxStream;
+use teo_result::Result;
+
+pub struct Stream {
+    inner: Mutex<Option<BoxStream<'static, Result<Vec<u8>>>>>,
+    content_type: String,
+    length: Option<u64>,
+}
+
+impl Stream {
+
+    pub fn new(stream: BoxStream<'static, Result<Vec<u8>>>, content_type: impl Into<String>, length: Option<u64>) -> Self {
+        Self {
+            inner: Mutex::new(Some(stream)),
+            content_type: content_type.into(),
+            length,
+        }
+    }
+
+    pub fn content_type(&self) -> &str {
+        self.content_type.as_str()
+    }
+
+    pub fn length(&self) -> Option<u64> {
+        self.length
+    }
+
+    /// Take the underlying byte stream out. A stream can only be consumed once, later calls
+    /// return `None`.
+    pub fn take(&self) -> Option<BoxStream<'static, Result<Vec<u8>>>> {
+        self.inner.lock().unwrap().take()
+    }
+}
+
+impl Debug for Stream {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug_struct = f.debug_struct("Stream");
+        debug_struct.field("content_type", &self.content_type);
+        debug_struct.field("length", &self.length);
+        debug_struct.finish()
+    }
+}
@@ -0,0 +1 @@
+pub mod readwrite;
\ No newline at end of file
@@ -0,0 +1,237 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+use chrono::{DateTime, TimeZone, Utc};
+use teo_result::{Error, Result};
+use crate::cookie::CookieKeys;
+use crate::utils::http_date;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+/// A cookie to be sent to the client with a `Set-Cookie` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    domain: Option<String>,
+    path: Option<String>,
+    expires: Option<DateTime<Utc>>,
+    max_age: Option<Duration>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            domain: None,
+            path: None,
+            expires: None,
+            max_age: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    /// A cookie which instructs the client to remove the cookie named `name`.
+    pub fn removal(name: impl Into<String>) -> Self {
+        Self::new(name, "").expires(Utc.timestamp_opt(0, 0).unwrap()).max_age(Duration::ZERO)
+    }
+
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn expires(mut self, expires: DateTime<Utc>) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    pub fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Sign the value with the current key.
+    pub fn signed(mut self, keys: &CookieKeys) -> Self {
+        self.value = keys.sign(&self.name, &self.value);
+        self
+    }
+
+    /// Encrypt the value with the current key.
+    pub fn encrypted(mut self, keys: &CookieKeys) -> Self {
+        self.value = keys.encrypt(&self.name, &self.value);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn value(&self) -> &str {
+        self.value.as_str()
+    }
+
+    /// Check that the name is a token and that the domain and path don't
+    /// contain `;` or control characters. The value is always encoded.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty() || !self.name.bytes().all(is_token_byte) {
+            return Err(Error::internal_server_error_message(format!("invalid cookie name `{}`", self.name.escape_debug())));
+        }
+        for (attribute, value) in [("domain", &self.domain), ("path", &self.path)] {
+            if let Some(value) = value {
+                if value.chars().any(|c| c == ';' || c.is_control()) {
+                    return Err(Error::internal_server_error_message(format!("invalid cookie {} `{}`", attribute, value.escape_debug())));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The value of the `Set-Cookie` header for this cookie.
+    pub fn to_header_value(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Display for Cookie {
+
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, encode_cookie_value(&self.value))?;
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if let Some(expires) = &self.expires {
+            write!(f, "; Expires={}", http_date(expires))?;
+        }
+        if let Some(max_age) = &self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        // browsers reject `SameSite=None` cookies which are not secure
+        if self.secure || self.same_site == Some(SameSite::None) {
+            f.write_str("; Secure")?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if let Some(same_site) = &self.same_site {
+            write!(f, "; SameSite={}", same_site.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// The RFC 7230 token characters.
+fn is_token_byte(byte: u8) -> bool {
+    matches!(byte, b'!' | b'#'..=b'\'' | b'*' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' | b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z')
+}
+
+/// Percent encode the bytes which are not allowed in a cookie value.
+fn encode_cookie_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E if byte != b'%' => result.push(byte as char),
+            _ => result.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use chrono::{TimeZone, Utc};
+    use super::{Cookie, SameSite};
+
+    #[test]
+    fn attributes_are_written_in_order() {
+        let cookie = Cookie::new("id", "1")
+            .same_site(SameSite::Lax)
+            .http_only(true)
+            .secure(true)
+            .max_age(Duration::from_secs(60))
+            .expires(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap())
+            .path("/")
+            .domain("example.com");
+        assert_eq!(cookie.to_header_value(), "id=1; Domain=example.com; Path=/; Expires=Tue, 02 Jan 2024 03:04:05 GMT; Max-Age=60; Secure; HttpOnly; SameSite=Lax");
+    }
+
+    #[test]
+    fn removal_expires_in_the_past() {
+        assert_eq!(Cookie::removal("id").to_header_value(), "id=; Expires=Thu, 01 Jan 1970 00:00:00 GMT; Max-Age=0");
+    }
+
+    #[test]
+    fn value_is_percent_encoded() {
+        assert_eq!(Cookie::new("id", "a b;c,d\"e\\f%g").to_header_value(), "id=a%20b%3Bc%2Cd%22e%5Cf%25g");
+        assert_eq!(Cookie::new("id", "é").to_header_value(), "id=%C3%A9");
+    }
+
+    #[test]
+    fn same_site_none_forces_secure() {
+        assert_eq!(Cookie::new("id", "1").same_site(SameSite::None).to_header_value(), "id=1; Secure; SameSite=None");
+        assert_eq!(Cookie::new("id", "1").same_site(SameSite::Strict).to_header_value(), "id=1; SameSite=Strict");
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        assert!(Cookie::new("session_id", "").validate().is_ok());
+        for name in ["", "a b", "a;b", "a=b", "a,b", "a\"b", "a\nb", "a(b)", "é"] {
+            assert!(Cookie::new(name, "").validate().is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn invalid_domains_and_paths_are_rejected() {
+        assert!(Cookie::new("id", "").domain("example.com").path("/a b").validate().is_ok());
+        assert!(Cookie::new("id", "").domain("example.com; HttpOnly").validate().is_err());
+        assert!(Cookie::new("id", "").domain("example.com\r\nX: y").validate().is_err());
+        assert!(Cookie::new("id", "").path("/;Secure").validate().is_err());
+        assert!(Cookie::new("id", "").path("/\0").validate().is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::request::Request;
 use crate::response::Response;
 
 pub trait IntoResponseWithPathedError {
@@ -10,3 +11,15 @@ impl IntoResponseWithPathedError for teo_result::Result<Response> {
         self
     }
 }
+
+/// Turns errors into responses. When a namespace has no formatter, errors
+/// are written with `Response::error`.
+pub trait ErrorFormatter: Send + Sync {
+    fn format(&self, error: &teo_result::Error, request: Option<&Request>) -> Response;
+}
+
+impl<F> ErrorFormatter for F where F: Fn(&teo_result::Error, Option<&Request>) -> Response + Send + Sync {
+    fn format(&self, error: &teo_result::Error, request: Option<&Request>) -> Response {
+        self(error, request)
+    }
+}
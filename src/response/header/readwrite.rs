@@ -43,6 +43,24 @@ impl HeaderMap {
     pub fn get(&self, key: impl AsRef<str>) -> Option<String> {
         self.inner.fields.lock().unwrap().get(key.as_ref()).map(|s| s.clone())
     }
+
+    pub fn remove(&self, key: impl AsRef<str>) -> Option<String> {
+        self.inner.fields.lock().unwrap().remove(key.as_ref())
+    }
+
+    /// Append a value to a comma separated header like `Vary`, unless it's
+    /// already listed.
+    pub fn append(&self, key: impl Into<String>, value: impl AsRef<str>) {
+        let mut fields = self.inner.fields.lock().unwrap();
+        let value = value.as_ref();
+        let existing = fields.entry(key.into()).or_default();
+        if existing.is_empty() {
+            existing.push_str(value);
+        } else if !existing.split(',').any(|v| v.trim().eq_ignore_ascii_case(value)) {
+            existing.push_str(", ");
+            existing.push_str(value);
+        }
+    }
 }
 
 pub struct HeaderMapInner {
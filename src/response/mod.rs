@@ -1,7 +1,12 @@
 pub mod response;
 pub mod header;
+pub mod cookie;
 pub mod body;
 pub mod error;
+pub mod problem;
 
 pub use response::Response;
-pub use header::readwrite::HeaderMap;
\ No newline at end of file
+pub use header::readwrite::HeaderMap;
+pub use cookie::readwrite::Cookie;
+pub use error::ErrorFormatter;
+pub use problem::ProblemJson;
@@ -0,0 +1,73 @@
+use indexmap::IndexMap;
+use teo_result::Error;
+use crate::request::Request;
+use crate::response::error::ErrorFormatter;
+use crate::response::Response;
+use crate::value::Value;
+
+/// Formats errors as RFC 7807 `application/problem+json` documents.
+///
+/// `type` is `about:blank` unless a `type_base` is given, in which case the
+/// error's title is appended to it in kebab case. Per path validation
+/// messages are kept in the `errors` extension member.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemJson {
+    pub type_base: Option<String>,
+}
+
+impl ProblemJson {
+
+    pub fn new() -> Self {
+        Self { type_base: None }
+    }
+
+    pub fn with_type_base(type_base: impl Into<String>) -> Self {
+        Self { type_base: Some(type_base.into()) }
+    }
+
+    pub fn document(&self, error: &Error, request: Option<&Request>) -> Value {
+        let title = error.inferred_title().to_string();
+        let mut document = IndexMap::new();
+        document.insert("type".to_owned(), Value::String(match &self.type_base {
+            Some(type_base) => format!("{}/{}", type_base.trim_end_matches('/'), kebab_case(&title)),
+            None => "about:blank".to_owned(),
+        }));
+        document.insert("title".to_owned(), Value::String(title));
+        document.insert("status".to_owned(), Value::Int(error.code as i32));
+        document.insert("detail".to_owned(), Value::String(error.message.clone()));
+        if let Some(request) = request {
+            document.insert("instance".to_owned(), Value::String(request.path().to_owned()));
+        }
+        if let Some(errors) = &error.errors {
+            document.insert("errors".to_owned(), Value::Dictionary(errors.iter().map(|(k, v)| (k.to_string(), Value::String(v.to_string()))).collect()));
+        }
+        Value::Dictionary(document)
+    }
+}
+
+impl ErrorFormatter for ProblemJson {
+
+    fn format(&self, error: &Error, request: Option<&Request>) -> Response {
+        let res = Response::teon(self.document(error, request));
+        res.set_code(error.code);
+        res.headers().set("content-type", "application/problem+json");
+        res
+    }
+}
+
+fn kebab_case(title: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in title.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !result.ends_with('-') {
+                result.push('-');
+            }
+            result.extend(c.to_lowercase());
+        } else if c.is_alphanumeric() {
+            result.push(c);
+        } else if !result.ends_with('-') && !result.is_empty() {
+            result.push('-');
+        }
+    }
+    result.trim_end_matches('-').to_owned()
+}
@@ -1,10 +1,19 @@
 use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use futures_util::{Stream, StreamExt};
+use serde_json::{Value as JsonValue};
 use crate::value::Value;
 use crate::teon;
 use teo_result::Result;
 use crate::response::body::Body;
+use crate::response::body::stream::Stream as BodyStream;
+use crate::response::body::sse::{Event, Sse};
+use crate::response::cookie::readwrite::Cookie;
+use crate::cookie::CookieKeys;
+use crate::utils::http_date;
+use chrono::{DateTime, Utc};
 use crate::response::header::readwrite::HeaderMap;
 
 #[derive(Clone)]
@@ -64,6 +73,47 @@ impl Response {
         res
     }
 
+    pub fn stream<S>(stream: S, content_type: &str) -> Response where S: Stream<Item = Result<Vec<u8>>> + Send + 'static {
+        Self::stream_body(BodyStream::new(stream.boxed(), content_type, None))
+    }
+
+    pub fn stream_with_length<S>(stream: S, content_type: &str, length: u64) -> Response where S: Stream<Item = Result<Vec<u8>>> + Send + 'static {
+        Self::stream_body(BodyStream::new(stream.boxed(), content_type, Some(length)))
+    }
+
+    /// Stream values as newline delimited JSON, one value per line.
+    pub fn ndjson<S>(stream: S) -> Response where S: Stream<Item = Result<Value>> + Send + 'static {
+        Self::stream(stream.map(|value| {
+            let json_value: JsonValue = value?.try_into()?;
+            let mut line = serde_json::to_vec(&json_value).unwrap();
+            line.push(b'\n');
+            Ok(line)
+        }), "application/x-ndjson")
+    }
+
+    pub fn sse<S>(stream: S) -> Response where S: Stream<Item = Result<Event>> + Send + 'static {
+        Self::sse_with_heartbeat(stream, Some(Duration::from_secs(15)))
+    }
+
+    pub fn sse_with_heartbeat<S>(stream: S, heartbeat: Option<Duration>) -> Response where S: Stream<Item = Result<Event>> + Send + 'static {
+        let mut inner = ResponseInner::new();
+        inner.headers.set("content-type", "text/event-stream");
+        inner.headers.set("cache-control", "no-cache");
+        inner.body = Body::sse(Sse::new(stream.boxed(), heartbeat));
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    fn stream_body(stream: BodyStream) -> Response {
+        let mut inner = ResponseInner::new();
+        inner.headers.set("content-type", stream.content_type());
+        inner.body = Body::stream(stream);
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
     pub fn redirect(path: impl Into<String>) -> Response {
         let res = Self::empty();
         res.set_code(301);
@@ -86,12 +136,56 @@ impl Response {
     pub fn body(&self) -> Body {
         self.inner.lock().unwrap().body.clone()
     }
+
+    pub fn set_body(&self, body: Body) {
+        self.inner.lock().unwrap().body = body;
+    }
+
+    /// Add a cookie, replacing a previously added cookie with the same name.
+    /// Each cookie is sent in its own `Set-Cookie` header. A cookie which
+    /// can't be written into a header is rejected.
+    pub fn add_cookie(&self, cookie: Cookie) -> Result<()> {
+        cookie.validate()?;
+        let mut inner = self.inner.lock().unwrap();
+        inner.cookies.retain(|c| c.name() != cookie.name());
+        inner.cookies.push(cookie);
+        Ok(())
+    }
+
+    /// Set the `Last-Modified` header, which conditional requests are checked
+    /// against.
+    pub fn set_last_modified(&self, last_modified: DateTime<Utc>) {
+        self.headers().set("last-modified", http_date(&last_modified));
+    }
+
+    pub fn add_signed_cookie(&self, cookie: Cookie, keys: &CookieKeys) -> Result<()> {
+        self.add_cookie(cookie.signed(keys))
+    }
+
+    pub fn add_encrypted_cookie(&self, cookie: Cookie, keys: &CookieKeys) -> Result<()> {
+        self.add_cookie(cookie.encrypted(keys))
+    }
+
+    /// Instruct the client to remove the cookie named `name`.
+    pub fn remove_cookie(&self, name: &str) -> Result<()> {
+        self.add_cookie(Cookie::removal(name))
+    }
+
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.inner.lock().unwrap().cookies.clone()
+    }
+
+    /// The `Set-Cookie` header values of this response.
+    pub fn set_cookie_headers(&self) -> Vec<String> {
+        self.inner.lock().unwrap().cookies.iter().map(|c| c.to_header_value()).collect()
+    }
 }
 
 pub struct ResponseInner {
     code: u16,
     headers: HeaderMap,
     body: Body,
+    cookies: Vec<Cookie>,
 }
 
 impl ResponseInner {
@@ -101,6 +195,7 @@ impl ResponseInner {
             code: 200,
             headers: HeaderMap::new(),
             body: Body::empty(),
+            cookies: vec![],
         }
     }
 }
@@ -111,6 +206,7 @@ impl Debug for Response {
         let mut debug_struct = f.debug_struct("Response");
         debug_struct.field("code", &self.inner.lock().unwrap().code);
         debug_struct.field("headers", &self.inner.lock().unwrap().headers);
+        debug_struct.field("cookies", &self.inner.lock().unwrap().cookies);
         debug_struct.finish()
     }
 }
@@ -8,6 +8,7 @@ use teo_parser::traits::resolved::Resolve;
 use teo_result::Result;
 use crate::handler::Handler;
 use crate::handler::handler::Method;
+use crate::middleware::middleware::empty_middleware;
 use crate::namespace::Namespace;
 use teo_result::Error;
 use crate::request;
@@ -26,9 +27,12 @@ pub fn load_handler(main_namespace: &mut Namespace, schema: &Schema, handler_dec
             format: HandlerInputFormat::Json,
             path: handler_declaration.string_path().clone(),
             ignore_prefix: false,
+            timeout: None,
             method: Method::Post,
             interface: None,
             url: None,
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
             namespace_path: handler_declaration.namespace_str_path().iter().map(|s| s.to_string()).collect(),
             call: Box::leak(Box::new(|ctx: request::Ctx| async {
                 Err(Error::not_found())
@@ -46,6 +50,9 @@ pub fn load_handler(main_namespace: &mut Namespace, schema: &Schema, handler_dec
             decorator_implementation.call.call(args, &mut handler)?;
         }
     }
+    if matches!(handler.format, HandlerInputFormat::Form) && matches!(handler.method, Method::Get | Method::Delete) {
+        return Err(Error::new(format!("handler `{}` accepts form input, which cannot be sent with {}", handler.path.join("."), handler.method.capitalized_name())));
+    }
     if (handler.method != Method::Post) || handler.url.is_some() {
         let parent_string_path = handler_declaration.parent_string_path();
         main_namespace.handler_map.add_record(
@@ -1,14 +1,27 @@
 use teo_parser::ast::schema::Schema;
 use teo_parser::diagnostics::diagnostics::Diagnostics;
+use teo_parser::traits::info_provider::InfoProvider;
+use teo_parser::traits::named_identifiable::NamedIdentifiable;
+use teo_parser::traits::resolved::Resolve;
 use teo_result::Result;
 use crate::namespace::Namespace;
+use crate::schema::fetch::fetch_decorator_arguments::fetch_decorator_arguments;
 use crate::schema::load::load_handler::load_handler;
 
 pub fn load_handler_group(main_namespace: &mut Namespace, schema: &Schema, handler_group_declaration: &teo_parser::ast::handler::HandlerGroupDeclaration, diagnostics: &mut Diagnostics) -> Result<()> {
     for handler_declaration in handler_group_declaration.handler_declarations() {
         load_handler(main_namespace, schema, handler_declaration, diagnostics)?;
     }
+    for decorator in handler_group_declaration.decorators() {
+        let decorator_declaration = schema.find_top_by_path(decorator.resolved()).unwrap().as_decorator_declaration().unwrap();
+        let Some(decorator_implementation) = main_namespace.handler_group_decorator_at_path(&decorator_declaration.str_path()) else {
+            continue
+        };
+        let call = decorator_implementation.call.clone();
+        let args = fetch_decorator_arguments(decorator, schema, handler_group_declaration, main_namespace, diagnostics)?;
+        if let Some(group) = main_namespace.handler_group_mut_at_path(&handler_group_declaration.str_path()) {
+            call.call(args, group)?;
+        }
+    }
     Ok(())
 }
-
-
@@ -9,6 +9,7 @@ use teo_parser::traits::resolved::Resolve;
 use teo_result::Result;
 use crate::handler::Handler;
 use crate::handler::handler::Method;
+use crate::middleware::middleware::empty_middleware;
 use crate::namespace::Namespace;
 use teo_result::Error;
 use crate::request;
@@ -28,9 +29,12 @@ pub fn load_handler_inclusion(main_namespace: &mut Namespace, schema: &Schema, h
             format: HandlerInputFormat::Json,
             path: handler_inclusion.string_path().clone(),
             ignore_prefix: false,
+            timeout: None,
             method: Method::Post,
             interface: None,
             url: None,
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
             namespace_path: handler_inclusion.namespace_str_path().iter().map(|s| s.to_string()).collect(),
             call: Box::leak(Box::new(|ctx: request::Ctx| async {
                 Err(Error::not_found())
@@ -8,6 +8,7 @@ use teo_parser::traits::resolved::Resolve;
 use teo_result::Result;
 use crate::handler::Handler;
 use crate::handler::handler::Method;
+use crate::middleware::middleware::empty_middleware;
 use crate::namespace::Namespace;
 use teo_result::Error;
 use crate::request;
@@ -26,9 +27,12 @@ pub fn load_handler_template(main_namespace: &mut Namespace, schema: &Schema, ha
             format: HandlerInputFormat::Json,
             path: handler_template_declaration.string_path().clone(),
             ignore_prefix: false,
+            timeout: None,
             method: Method::Post,
             interface: None,
             url: None,
+            middleware_uses: vec![],
+            middleware_stack: empty_middleware(),
             namespace_path: handler_template_declaration.namespace_str_path().iter().map(|s| s.to_string()).collect(),
             call: Box::leak(Box::new(|ctx: request::Ctx| async {
                 Err(Error::not_found())
@@ -9,6 +9,8 @@ use teo_parser::traits::identifiable::Identifiable;
 use teo_parser::traits::info_provider::InfoProvider;
 use teo_parser::traits::named_identifiable::NamedIdentifiable;
 use teo_parser::traits::node_trait::NodeTrait;
+use teo_parser::traits::resolved::Resolve;
+use crate::graphql::build_graphql_schema;
 use crate::namespace::Namespace;
 use teo_result::Result;
 use crate::schema::load::load_admin::load_admin;
@@ -25,7 +27,7 @@ use crate::schema::load::load_interface::load_interface;
 use crate::schema::load::load_model::load_model;
 use crate::schema::load::load_model_opposite_relations::load_model_opposite_relations;
 use crate::schema::load::load_server::load_server;
-use crate::schema::load::load_use_middlewares::load_use_middlewares;
+use crate::schema::load::load_use_middlewares::{load_handler_middleware_stacks, load_use_middlewares};
 
 pub async fn load_schema(main_namespace: &mut Namespace, schema: &Schema, ignores_loading: bool) -> Result<()> {
 
@@ -238,6 +240,16 @@ pub async fn load_schema(main_namespace: &mut Namespace, schema: &Schema, ignore
     // load model opposite relations
     load_model_opposite_relations(main_namespace);
 
+    // load handler and handler group middlewares
+    if !ignores_loading {
+        load_handler_middleware_stacks(main_namespace).await?;
+    }
+
+    // load graphql schema
+    if !ignores_loading && includes_graphql_endpoint(schema) {
+        main_namespace.graphql_schema = Some(build_graphql_schema(main_namespace)?);
+    }
+
     // diagnostics
     if !ignores_loading {
         print_diagnostics(&diagnostics, true);
@@ -247,4 +259,18 @@ pub async fn load_schema(main_namespace: &mut Namespace, schema: &Schema, ignore
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn includes_graphql_endpoint(schema: &Schema) -> bool {
+    for model_declaration in schema.models() {
+        if !model_declaration.is_available() {
+            continue
+        }
+        for handler_inclusion in model_declaration.handler_inclusions() {
+            if handler_inclusion.resolved().template_path == ["std", "graphql", "endpoint"] {
+                return true;
+            }
+        }
+    }
+    false
+}
@@ -5,9 +5,12 @@ use teo_parser::r#type::Type;
 use teo_parser::traits::has_availability::HasAvailability;
 use teo_parser::traits::info_provider::InfoProvider;
 use teo_parser::traits::resolved::Resolve;
-use crate::config::server::Server;
+use std::time::Duration;
+use crate::config::server::{Compression, Encoding, Server};
+use crate::middleware::timeout::timeout_from_seconds;
 use crate::namespace::Namespace;
-use teo_result::Result;
+use teo_result::{Error, Result};
+use crate::value::Value;
 use crate::schema::fetch::fetch_expression::fetch_expression_or_null;
 
 pub fn load_server(main_namespace: &mut Namespace, schema: &Schema, server: &Config, diagnostics: &mut Diagnostics) -> Result<()> {
@@ -16,11 +19,74 @@ pub fn load_server(main_namespace: &mut Namespace, schema: &Schema, server: &Con
     let path_prefix: Option<String> = fetch_expression_or_null(server.get_item("pathPrefix"), schema, server, path_prefix_expect, main_namespace, diagnostics)?.try_into()?;
     let bind_expect = config_decl.get_field("bind").unwrap().type_expr().resolved();
     let bind: (String, i32) = fetch_expression_or_null(server.get_item("bind"), schema, server, bind_expect, main_namespace, diagnostics)?.try_into()?;
+    let compression = match config_decl.get_field("compression") {
+        Some(compression_field) => {
+            let compression_expect = compression_field.type_expr().resolved();
+            let compression = fetch_expression_or_null(server.get_item("compression"), schema, server, compression_expect, main_namespace, diagnostics)?;
+            load_compression(&compression)?
+        }
+        None => None,
+    };
+    let binary = match config_decl.get_field("binary") {
+        Some(binary_field) => {
+            let binary_expect = binary_field.type_expr().resolved();
+            let binary: Option<bool> = fetch_expression_or_null(server.get_item("binary"), schema, server, binary_expect, main_namespace, diagnostics)?.try_into()?;
+            binary.unwrap_or(false)
+        }
+        None => false,
+    };
+    let timeout = match config_decl.get_field("timeout") {
+        Some(timeout_field) => {
+            let timeout_expect = timeout_field.type_expr().resolved();
+            let timeout = fetch_expression_or_null(server.get_item("timeout"), schema, server, timeout_expect, main_namespace, diagnostics)?;
+            load_timeout(&timeout)?
+        }
+        None => None,
+    };
     let server_conf = Server {
         bind,
         path_prefix,
+        compression,
+        binary,
+        timeout,
     };
     let dest_namespace = main_namespace.namespace_mut_or_create_at_path(&server.namespace_str_path());
     dest_namespace.server = Some(server_conf);
     Ok(())
-}
\ No newline at end of file
+}
+
+/// `compression` is either a bool, or a dictionary with `threshold` and
+/// `encodings`.
+fn load_compression(value: &Value) -> Result<Option<Compression>> {
+    match value {
+        Value::Null | Value::Bool(false) => Ok(None),
+        Value::Bool(true) => Ok(Some(Compression::default())),
+        Value::Dictionary(map) => {
+            let mut compression = Compression::default();
+            if let Some(threshold) = map.get("threshold") {
+                compression.threshold = threshold.to_int64().ok_or_else(|| Error::new("compression threshold should be int"))?.max(0) as usize;
+            }
+            if let Some(encodings) = map.get("encodings") {
+                let Some(encodings) = encodings.as_array() else {
+                    return Err(Error::new("compression encodings should be array"));
+                };
+                compression.encodings = encodings.iter().map(|e| {
+                    let name = match e {
+                        Value::InterfaceEnumVariant(variant) => variant.value.as_str(),
+                        _ => e.as_str().unwrap_or(""),
+                    };
+                    Encoding::from_name(name).ok_or_else(|| Error::new(format!("unknown compression encoding: {}", name)))
+                }).collect::<Result<Vec<Encoding>>>()?;
+            }
+            Ok(Some(compression))
+        }
+        _ => Err(Error::new("invalid compression config")),
+    }
+}
+/// `timeout` is the amount of seconds, an int or a float.
+fn load_timeout(value: &Value) -> Result<Option<Duration>> {
+    match value {
+        Value::Null => Ok(None),
+        value => Ok(Some(timeout_from_seconds(value)?)),
+    }
+}
@@ -1,14 +1,20 @@
+use std::collections::BTreeMap;
 use teo_parser::ast::schema::Schema;
 use teo_parser::traits::info_provider::InfoProvider;
 use teo_parser::traits::resolved::Resolve;
-use teo_result::Result;
+use teo_result::{Error, Result};
 use async_recursion::async_recursion;
 use teo_parser::ast::arith_expr::ArithExpr;
 use teo_parser::diagnostics::diagnostics::Diagnostics;
 use crate::arguments::Arguments;
-use crate::middleware::{Block, Use};
-use crate::middleware::middleware::{combine_middleware, empty_middleware, Middleware};
+use crate::middleware::{Block, Definition, Use};
+use crate::middleware::middleware::{combine_middleware, Middleware};
 use crate::namespace::Namespace;
+use crate::handler::Handler;
+use crate::middleware::binary::binary_response_middleware;
+use crate::middleware::compression::compression_middleware;
+use crate::middleware::timeout::timeout_middleware;
+use crate::handler::input::path_args::path_args_middleware;
 use crate::schema::fetch::fetch_argument_list::{fetch_argument_list, fetch_argument_list_or_empty};
 
 pub(super) async fn load_use_middlewares(main_namespace: &mut Namespace, schema: &Schema, diagnostics: &mut Diagnostics) -> Result<()> {
@@ -37,11 +43,7 @@ pub(super) async fn load_use_middlewares(main_namespace: &mut Namespace, schema:
                             _ => ()
                         }
                     }
-                    block.uses.push(Use {
-                        path: path.iter().map(|s| s.to_string()).collect(),
-                        creator,
-                        arguments,
-                    })
+                    block.uses.push(Use::new(path.iter().map(|s| s.to_string()).collect(), creator, arguments)?)
                 }
             }
         }
@@ -49,8 +51,19 @@ pub(super) async fn load_use_middlewares(main_namespace: &mut Namespace, schema:
         dest_namespace.middlewares_block = Some(block);
     }
 
-    // load middleware stack
-    load_middleware_stack(main_namespace, empty_middleware()).await?;
+    // load middleware stack, response encoding wraps everything else
+    let mut root_middlewares = vec![];
+    if let Some(timeout) = main_namespace.server.as_ref().and_then(|s| s.timeout) {
+        root_middlewares.push(timeout_middleware(timeout));
+    }
+    if main_namespace.server.as_ref().is_some_and(|s| s.binary) {
+        root_middlewares.push(binary_response_middleware());
+    }
+    if let Some(compression) = main_namespace.server.as_ref().and_then(|s| s.compression.clone()) {
+        root_middlewares.push(compression_middleware(compression));
+    }
+    let root_stack = combine_middleware(root_middlewares);
+    load_middleware_stack(main_namespace, root_stack).await?;
     Ok(())
 }
 
@@ -59,8 +72,10 @@ async fn load_middleware_stack(namespace: &mut Namespace, parent_stack: &'static
     if let Some(block) = &namespace.middlewares_block {
         let mut middlewares = vec![];
         middlewares.push(parent_stack);
-        for r#use in &block.uses {
-            let middleware = r#use.creator.call(r#use.arguments.clone()).await?;
+        let mut uses: Vec<&Use> = block.uses.iter().collect();
+        uses.sort_by(|a, b| b.priority.cmp(&a.priority));
+        for r#use in uses {
+            let middleware = r#use.create().await?;
             middlewares.push(middleware);
         }
         middlewares.reverse();
@@ -72,4 +87,75 @@ async fn load_middleware_stack(namespace: &mut Namespace, parent_stack: &'static
         load_middleware_stack(child_namespace, namespace.middleware_stack).await?;
     }
     Ok(())
+}
+
+pub(super) async fn load_handler_middleware_stacks(main_namespace: &mut Namespace) -> Result<()> {
+    let mut definitions = BTreeMap::new();
+    collect_middleware_definitions(main_namespace, &mut definitions);
+    load_handler_middleware_stack(main_namespace, &definitions).await
+}
+
+fn collect_middleware_definitions(namespace: &Namespace, definitions: &mut BTreeMap<Vec<String>, Definition>) {
+    for definition in namespace.middlewares.values() {
+        definitions.insert(definition.path.clone(), definition.clone());
+    }
+    for child_namespace in namespace.namespaces.values() {
+        collect_middleware_definitions(child_namespace, definitions);
+    }
+}
+
+/// Build the stacks of handler groups and handlers from their own middleware uses. They
+/// don't contain the namespace stack: a request runs through the namespace stack, the group
+/// stack and the handler stack, outer to inner, before the handler is called.
+#[async_recursion]
+async fn load_handler_middleware_stack(namespace: &mut Namespace, definitions: &BTreeMap<Vec<String>, Definition>) -> Result<()> {
+    for handler in namespace.handlers.values_mut() {
+        let uses = resolve_middleware_uses(&handler.middleware_uses, definitions)?;
+        handler.middleware_stack = stack_handler_middlewares(handler, stack_middleware_uses(uses).await?);
+    }
+    for group in namespace.handler_groups.values_mut().chain(namespace.model_handler_groups.values_mut()) {
+        let uses = resolve_middleware_uses(&group.middleware_uses, definitions)?;
+        group.middleware_stack = stack_middleware_uses(uses).await?;
+        for handler in group.handlers.values_mut() {
+            let uses = resolve_middleware_uses(&handler.middleware_uses, definitions)?;
+            handler.middleware_stack = stack_handler_middlewares(handler, stack_middleware_uses(uses).await?);
+        }
+    }
+    for child_namespace in namespace.namespaces.values_mut() {
+        load_handler_middleware_stack(child_namespace, definitions).await?;
+    }
+    Ok(())
+}
+
+fn resolve_middleware_uses(uses: &Vec<(Vec<String>, Arguments)>, definitions: &BTreeMap<Vec<String>, Definition>) -> Result<Vec<Use>> {
+    let mut result = vec![];
+    for (path, arguments) in uses {
+        let Some(definition) = definitions.get(path) else {
+            return Err(Error::new(format!("middleware `{}` is not found", path.join("."))));
+        };
+        result.push(Use::new(path.clone(), definition.creator.clone(), arguments.clone())?);
+    }
+    result.sort_by(|a, b| b.priority.cmp(&a.priority));
+    Ok(result)
+}
+
+async fn stack_middleware_uses(uses: Vec<Use>) -> Result<&'static dyn Middleware> {
+    let mut middlewares = vec![];
+    for r#use in &uses {
+        let middleware = r#use.create().await?;
+        middlewares.push(middleware);
+    }
+    middlewares.reverse();
+    Ok(combine_middleware(middlewares))
+}
+
+fn stack_handler_middlewares(handler: &Handler, stack: &'static dyn Middleware) -> &'static dyn Middleware {
+    let stack = match handler.timeout {
+        Some(timeout) => combine_middleware(vec![timeout_middleware(timeout), stack]),
+        None => stack,
+    };
+    match handler.custom_url_args_path() {
+        Some(interface_path) if handler.has_custom_url_args() => combine_middleware(vec![path_args_middleware(interface_path), stack]),
+        _ => stack,
+    }
 }
\ No newline at end of file
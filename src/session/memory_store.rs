@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use teo_result::Result;
+use crate::connection::transaction;
+use crate::session::store::SessionStore;
+use crate::value::Value;
+
+/// Keeps sessions in the memory of this process.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    sessions: Mutex<HashMap<String, (Value, DateTime<Utc>)>>,
+}
+
+impl MemoryStore {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemoryStore {
+
+    async fn load(&self, id: &str, _transaction_ctx: &transaction::Ctx) -> Result<Option<Value>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get(id) {
+            Some((_, expires_at)) if *expires_at <= Utc::now() => {
+                sessions.remove(id);
+                Ok(None)
+            }
+            Some((data, _)) => Ok(Some(data.clone())),
+            None => Ok(None),
+        }
+    }
+
+    async fn save(&self, id: &str, data: &Value, expires_at: DateTime<Utc>, _transaction_ctx: &transaction::Ctx) -> Result<()> {
+        let now = Utc::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, (_, expires_at)| *expires_at > now);
+        sessions.insert(id.to_owned(), (data.clone(), expires_at));
+        Ok(())
+    }
+
+    async fn destroy(&self, id: &str, _transaction_ctx: &transaction::Ctx) -> Result<()> {
+        self.sessions.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use crate::connection::{self, transaction};
+    use crate::namespace::Namespace;
+    use crate::session::store::SessionStore;
+    use crate::teon;
+    use super::MemoryStore;
+
+    fn transaction_ctx() -> transaction::Ctx {
+        let namespace: &'static Namespace = Box::leak(Box::new(Namespace::main()));
+        transaction::Ctx::new(connection::Ctx::from_namespace(namespace))
+    }
+
+    #[tokio::test]
+    async fn saved_sessions_are_loaded_until_destroyed() {
+        let store = MemoryStore::new();
+        let ctx = transaction_ctx();
+        store.save("a", &teon!({"user": 1}), Utc::now() + Duration::hours(1), &ctx).await.unwrap();
+        assert_eq!(store.load("a", &ctx).await.unwrap(), Some(teon!({"user": 1})));
+        assert_eq!(store.load("b", &ctx).await.unwrap(), None);
+        store.destroy("a", &ctx).await.unwrap();
+        assert_eq!(store.load("a", &ctx).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn expired_sessions_are_not_loaded() {
+        let store = MemoryStore::new();
+        let ctx = transaction_ctx();
+        store.save("a", &teon!({"user": 1}), Utc::now() - Duration::seconds(1), &ctx).await.unwrap();
+        assert_eq!(store.load("a", &ctx).await.unwrap(), None);
+    }
+}
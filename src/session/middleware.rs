@@ -0,0 +1,219 @@
+use std::sync::Arc;
+use std::time::Duration;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use indexmap::IndexMap;
+use rand::RngCore;
+use teo_result::{Error, Result};
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::request::Ctx;
+use crate::response::cookie::readwrite::{Cookie, SameSite};
+use crate::session::store::SessionStore;
+use crate::utils::{expires_at, expiry_ttl};
+use crate::value::Value;
+
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    pub cookie_name: String,
+    pub ttl: Duration,
+    pub secure: bool,
+    pub same_site: SameSite,
+}
+
+impl Default for SessionOptions {
+
+    fn default() -> Self {
+        Self {
+            cookie_name: "teo_session".to_owned(),
+            ttl: Duration::from_secs(60 * 60 * 24),
+            secure: false,
+            same_site: SameSite::Lax,
+        }
+    }
+}
+
+/// A middleware which loads the session into the request ctx and writes it
+/// back to `store` when it's modified, regenerated or destroyed.
+///
+/// The store is accessed outside the request's transaction, so the session
+/// isn't lost when the handler's changes are rolled back. A regenerated
+/// session is saved under its new id before the old id is destroyed.
+pub fn session_middleware(store: Arc<dyn SessionStore>, options: SessionOptions) -> Result<&'static dyn Middleware> {
+    if options.ttl.is_zero() {
+        return Err(Error::new("session ttl must be positive"));
+    }
+    let ttl = expiry_ttl(options.ttl).ok_or_else(|| Error::new("session ttl is too large"))?;
+    let store: &'static Arc<dyn SessionStore> = Box::leak(Box::new(store));
+    let options: &'static SessionOptions = Box::leak(Box::new(options));
+    Ok(Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+        let store_ctx = ctx.transaction_ctx().no_transaction_copy();
+        let mut id = ctx.request().cookie(&options.cookie_name)?.map(|c| c.decoded_value());
+        let mut loaded = None;
+        if let Some(session_id) = &id {
+            loaded = store.load(session_id, &store_ctx).await?;
+            if loaded.is_none() {
+                id = None;
+            }
+        }
+        let loaded = loaded.unwrap_or(Value::Dictionary(IndexMap::new()));
+        ctx.set_session(loaded.clone());
+        let response = next.call(ctx.clone()).await?;
+        if ctx.data().get::<bool>("session:destroy").is_some() {
+            if let Some(id) = &id {
+                store.destroy(id, &store_ctx).await?;
+            }
+            response.remove_cookie(&options.cookie_name)?;
+            return Ok(response);
+        }
+        let session = ctx.session().unwrap_or(Value::Dictionary(IndexMap::new()));
+        let regenerate = ctx.data().get::<bool>("session:regenerate").is_some();
+        if session == loaded && (!regenerate || id.is_none()) {
+            return Ok(response);
+        }
+        let replaced = if regenerate { id.take() } else { None };
+        let id = id.unwrap_or_else(generate_session_id);
+        store.save(&id, &session, expires_at(ttl)?, &store_ctx).await?;
+        if let Some(replaced) = &replaced {
+            store.destroy(replaced, &store_ctx).await?;
+        }
+        response.add_cookie(Cookie::new(&options.cookie_name, id)
+            .path("/")
+            .max_age(options.ttl)
+            .http_only(true)
+            .secure(options.secure)
+            .same_site(options.same_site))?;
+        Ok(response)
+    })))
+}
+
+fn generate_session_id() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use indexmap::IndexMap;
+    use teo_result::Result;
+    use crate::connection::transaction;
+    use crate::middleware::next::Next;
+    use crate::request::Ctx;
+    use crate::request::stub::stub_ctx;
+    use crate::response::Response;
+    use crate::session::{MemoryStore, SessionStore};
+    use crate::value::Value;
+    use super::{session_middleware, SessionOptions};
+
+    /// A memory store which records the ids it's asked to save and destroy.
+    #[derive(Debug, Default)]
+    struct RecordingStore {
+        store: MemoryStore,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingStore {
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl SessionStore for RecordingStore {
+
+        async fn load(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<Option<Value>> {
+            self.store.load(id, transaction_ctx).await
+        }
+
+        async fn save(&self, id: &str, data: &Value, expires_at: DateTime<Utc>, transaction_ctx: &transaction::Ctx) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("save {}", id));
+            self.store.save(id, data, expires_at, transaction_ctx).await
+        }
+
+        async fn destroy(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<()> {
+            self.calls.lock().unwrap().push(format!("destroy {}", id));
+            self.store.destroy(id, transaction_ctx).await
+        }
+    }
+
+    fn session(value: i64) -> Value {
+        Value::Dictionary(IndexMap::from([("value".to_owned(), Value::Int64(value))]))
+    }
+
+    async fn store_with_session() -> Arc<RecordingStore> {
+        let store = Arc::new(RecordingStore::default());
+        let ctx = stub_ctx("GET", vec![]);
+        store.store.save("old", &session(1), Utc::now() + chrono::Duration::seconds(60), &ctx.transaction_ctx()).await.unwrap();
+        store
+    }
+
+    async fn call(store: Arc<RecordingStore>, cookie: Option<&str>, handler: fn(&Ctx)) -> Response {
+        let middleware = session_middleware(store, SessionOptions::default()).unwrap();
+        let next: &'static dyn Next = Box::leak(Box::new(move |ctx: Ctx| async move {
+            handler(&ctx);
+            Ok(Response::empty())
+        }));
+        let headers = cookie.map(|cookie| vec![("cookie", cookie)]).unwrap_or_default();
+        middleware.call(stub_ctx("GET", headers), next).await.unwrap()
+    }
+
+    #[test]
+    fn ttl_must_be_positive() {
+        let options = SessionOptions { ttl: Duration::ZERO, ..SessionOptions::default() };
+        assert!(session_middleware(Arc::new(MemoryStore::new()), options).is_err());
+        assert!(session_middleware(Arc::new(MemoryStore::new()), SessionOptions::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn session_is_loaded_by_cookie() {
+        let store = store_with_session().await;
+        let res = call(store.clone(), Some("teo_session=old"), |ctx| {
+            assert_eq!(ctx.session(), Some(session(1)));
+        }).await;
+        assert!(res.cookies().is_empty());
+        assert!(store.calls().is_empty());
+        call(store.clone(), Some("teo_session=unknown"), |ctx| {
+            assert_eq!(ctx.session(), Some(Value::Dictionary(IndexMap::new())));
+        }).await;
+    }
+
+    #[tokio::test]
+    async fn session_is_saved_only_when_changed() {
+        let store = store_with_session().await;
+        let res = call(store.clone(), Some("teo_session=old"), |ctx| ctx.set_session(session(1))).await;
+        assert!(res.cookies().is_empty());
+        assert!(store.calls().is_empty());
+        let res = call(store.clone(), Some("teo_session=old"), |ctx| ctx.set_session(session(2))).await;
+        assert_eq!(store.calls(), vec!["save old"]);
+        assert_eq!(res.cookies()[0].value(), "old");
+        assert_eq!(store.store.load("old", &stub_ctx("GET", vec![]).transaction_ctx()).await.unwrap(), Some(session(2)));
+    }
+
+    #[tokio::test]
+    async fn regenerated_session_is_saved_before_the_old_one_is_destroyed() {
+        let store = store_with_session().await;
+        let res = call(store.clone(), Some("teo_session=old"), |ctx| ctx.regenerate_session()).await;
+        let id = res.cookies()[0].value().to_owned();
+        assert_ne!(id, "old");
+        assert_eq!(store.calls(), vec![format!("save {}", id), "destroy old".to_owned()]);
+        let transaction_ctx = stub_ctx("GET", vec![]).transaction_ctx();
+        assert_eq!(store.store.load(&id, &transaction_ctx).await.unwrap(), Some(session(1)));
+        assert_eq!(store.store.load("old", &transaction_ctx).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn destroyed_session_removes_the_cookie() {
+        let store = store_with_session().await;
+        let res = call(store.clone(), Some("teo_session=old"), |ctx| ctx.destroy_session()).await;
+        assert_eq!(store.calls(), vec!["destroy old"]);
+        let cookie = &res.cookies()[0];
+        assert_eq!(cookie.name(), "teo_session");
+        assert!(cookie.to_header_value().contains("Max-Age=0"));
+    }
+}
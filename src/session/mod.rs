@@ -0,0 +1,9 @@
+pub mod store;
+pub mod memory_store;
+pub mod model_store;
+pub mod middleware;
+
+pub use store::SessionStore;
+pub use memory_store::MemoryStore;
+pub use model_store::ModelStore;
+pub use middleware::{session_middleware, SessionOptions};
@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use key_path::path;
+use serde_json::Value as JsonValue;
+use teo_result::{Error, Result};
+use crate::connection::transaction;
+use crate::model::{Model, Object};
+use crate::teon;
+use crate::value::Value;
+use crate::session::store::SessionStore;
+
+/// Keeps sessions in a model, so they live in whichever database the model's
+/// connection points to.
+///
+/// The model needs a `String` field `id`, a `String` field `data` which holds
+/// the session as json, and a `DateTime` field `expiresAt`.
+#[derive(Debug, Clone)]
+pub struct ModelStore {
+    model_path: Vec<String>,
+}
+
+impl ModelStore {
+
+    pub fn new(model_path: Vec<String>) -> Self {
+        Self { model_path }
+    }
+
+    fn model(&self, transaction_ctx: &transaction::Ctx) -> Result<&'static Model> {
+        transaction_ctx.namespace().model_at_path(&self.model_path.iter().map(AsRef::as_ref).collect()).ok_or_else(|| {
+            Error::internal_server_error_message(format!("session model `{}` is not found", self.model_path.join(".")))
+        })
+    }
+
+    async fn find(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<Option<Object>> {
+        let model = self.model(transaction_ctx)?;
+        transaction_ctx.find_unique(model, &teon!({"where": {"id": id}}), None, path![]).await
+    }
+}
+
+#[async_trait]
+impl SessionStore for ModelStore {
+
+    async fn load(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<Option<Value>> {
+        let Some(object) = self.find(id, transaction_ctx).await? else {
+            return Ok(None);
+        };
+        let expires_at: DateTime<Utc> = object.get("expiresAt")?;
+        if expires_at <= Utc::now() {
+            object.delete().await?;
+            return Ok(None);
+        }
+        let data: String = object.get("data")?;
+        let json: JsonValue = serde_json::from_str(&data).map_err(|e| Error::internal_server_error_message(e.to_string()))?;
+        Ok(Some(Value::from(json)))
+    }
+
+    async fn save(&self, id: &str, data: &Value, expires_at: DateTime<Utc>, transaction_ctx: &transaction::Ctx) -> Result<()> {
+        let json: JsonValue = data.try_into()?;
+        let values = teon!({
+            "data": json.to_string(),
+            "expiresAt": expires_at,
+        });
+        let object = match self.find(id, transaction_ctx).await? {
+            Some(object) => object,
+            None => transaction_ctx.create_object(self.model(transaction_ctx)?, &teon!({"id": id}), None).await?,
+        };
+        object.set_teon(&values).await?;
+        object.save().await
+    }
+
+    async fn destroy(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<()> {
+        if let Some(object) = self.find(id, transaction_ctx).await? {
+            object.delete().await?;
+        }
+        Ok(())
+    }
+}
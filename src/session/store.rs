@@ -0,0 +1,17 @@
+use std::fmt::Debug;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use teo_result::Result;
+use crate::connection::transaction;
+use crate::value::Value;
+
+#[async_trait]
+pub trait SessionStore: Send + Sync + Debug {
+
+    /// Load the session data, `None` if the session doesn't exist or expired.
+    async fn load(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<Option<Value>>;
+
+    async fn save(&self, id: &str, data: &Value, expires_at: DateTime<Utc>, transaction_ctx: &transaction::Ctx) -> Result<()>;
+
+    async fn destroy(&self, id: &str, transaction_ctx: &transaction::Ctx) -> Result<()>;
+}
@@ -0,0 +1,17 @@
+use serde_json::Value as JsonValue;
+use crate::handler::batch::execute_batch;
+use crate::namespace::Namespace;
+use crate::request;
+use crate::response::Response;
+use crate::value::Value;
+
+pub(super) fn load_batch_library(std_namespace: &mut Namespace) {
+
+    let batch_namespace = std_namespace.namespace_mut_or_create("batch");
+
+    batch_namespace.define_handler_template("execute", |req_ctx: request::Ctx| async move {
+        let body: JsonValue = req_ctx.body().try_into()?;
+        let results = execute_batch(&req_ctx, &body).await?;
+        Ok(Response::data(Value::Array(results)))
+    });
+}
@@ -1,5 +1,10 @@
+use std::collections::BTreeMap;
+use teo_result::{Error, Result};
+use crate::arguments::Arguments;
 use crate::handler::handler::Method;
+use crate::middleware::timeout::timeout_from_seconds;
 use crate::namespace::Namespace;
+use crate::value::Value;
 
 pub(in crate::stdlib) fn load_handler_decorators(namespace: &mut Namespace) {
 
@@ -16,4 +21,30 @@ pub(in crate::stdlib) fn load_handler_decorators(namespace: &mut Namespace) {
         handler.interface = interface;
         Ok(())
     });
-}
\ No newline at end of file
+
+    namespace.define_handler_decorator("timeout", |arguments, handler| {
+        let seconds: &Value = arguments.get("seconds")?;
+        handler.timeout = Some(timeout_from_seconds(seconds)?);
+        Ok(())
+    });
+
+    namespace.define_handler_decorator("use", |arguments, handler| {
+        let (middleware, middleware_arguments) = middleware_use_arguments(arguments)?;
+        handler.use_middleware(middleware.split(".").collect(), middleware_arguments);
+        Ok(())
+    });
+}
+
+/// The middleware path and its arguments of a `@use` decorator.
+pub(super) fn middleware_use_arguments(arguments: Arguments) -> Result<(String, Arguments)> {
+    let middleware: String = arguments.get("middleware")?;
+    let middleware_arguments: Option<Value> = arguments.get_optional("arguments")?;
+    let middleware_arguments = match middleware_arguments {
+        Some(value) => match value {
+            Value::Dictionary(map) => Arguments::new(map.into_iter().collect::<BTreeMap<String, Value>>()),
+            _ => Err(Error::new("middleware arguments should be a dictionary"))?,
+        },
+        None => Arguments::default(),
+    };
+    Ok((middleware, middleware_arguments))
+}
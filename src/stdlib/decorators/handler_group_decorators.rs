@@ -0,0 +1,11 @@
+use crate::namespace::Namespace;
+use crate::stdlib::decorators::handler_decorators::middleware_use_arguments;
+
+pub(in crate::stdlib) fn load_handler_group_decorators(namespace: &mut Namespace) {
+
+    namespace.define_handler_group_decorator("use", |arguments, group| {
+        let (middleware, middleware_arguments) = middleware_use_arguments(arguments)?;
+        group.use_middleware(middleware.split(".").collect(), middleware_arguments);
+        Ok(())
+    });
+}
@@ -6,5 +6,6 @@ pub(super) mod enum_decorators;
 pub(super) mod enum_member_decorators;
 pub(super) mod interface_decorators;
 pub(super) mod handler_decorators;
+pub(super) mod handler_group_decorators;
 pub(super) mod indexable_decorators;
 pub(super) mod model_indexable_decorators;
\ No newline at end of file
@@ -248,4 +248,12 @@ pub(in crate::stdlib) fn load_model_field_decorators(namespace: &mut Namespace)
         field.migration = Some(migration);
         Ok(())
     });
+
+    namespace.define_model_field_decorator("storage", |arguments, field| {
+        let name: Option<String> = arguments.get_optional("name")?;
+        let url: Option<bool> = arguments.get_optional("url")?;
+        field.data.insert("storage:name".to_owned(), Value::String(name.unwrap_or("default".to_owned())));
+        field.data.insert("storage:url".to_owned(), Value::Bool(url.unwrap_or(false)));
+        Ok(())
+    });
 }
@@ -0,0 +1,21 @@
+use serde_json::Value as JsonValue;
+use teo_result::Error;
+use crate::namespace::Namespace;
+use crate::request;
+use crate::response::Response;
+
+pub(super) fn load_graphql_library(std_namespace: &mut Namespace) {
+
+    let graphql_namespace = std_namespace.namespace_mut_or_create("graphql");
+
+    graphql_namespace.define_handler_template("endpoint", |req_ctx: request::Ctx| async move {
+        let Some(schema) = req_ctx.namespace().graphql_schema.as_ref() else {
+            return Err(Error::internal_server_error_message("graphql schema is not loaded"));
+        };
+        let body: JsonValue = req_ctx.body().try_into()?;
+        let request: async_graphql::Request = serde_json::from_value(body).map_err(|e| Error::invalid_request_message(format!("invalid graphql request: {}", e)))?;
+        let response = schema.execute(request.data(req_ctx.clone())).await;
+        let content = serde_json::to_string(&response).map_err(|e| Error::new(e.to_string()))?;
+        Ok(Response::string(content, "application/json"))
+    });
+}
@@ -184,6 +184,8 @@ pub(super) fn load_identity_library(std_namespace: &mut Namespace) {
         };
         let token_issuer = token_issuer.as_pipeline().unwrap();
         let token_string: String = credentials_pipeline_ctx.run_pipeline(token_issuer).await?;
+        // a signed in client must not keep using a session id issued before
+        req_ctx.regenerate_session();
         // Output to the client
         let include = input.get("include");
         let select = input.get("select");
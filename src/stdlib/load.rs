@@ -3,12 +3,17 @@ use crate::stdlib::admin::load_admin_library;
 use crate::stdlib::decorators::enum_decorators::load_enum_decorators;
 use crate::stdlib::decorators::enum_member_decorators::load_enum_member_decorators;
 use crate::stdlib::decorators::handler_decorators::load_handler_decorators;
+use crate::stdlib::decorators::handler_group_decorators::load_handler_group_decorators;
 use crate::stdlib::decorators::interface_decorators::load_interface_decorators;
 use crate::stdlib::decorators::model_decorators::load_model_decorators;
 use crate::stdlib::decorators::model_field_decorators::load_model_field_decorators;
 use crate::stdlib::decorators::model_property_decorators::load_model_property_decorators;
 use crate::stdlib::decorators::model_relation_decorators::load_model_relation_decorators;
 use crate::stdlib::middlewares::log_request::load_log_request_middleware;
+use crate::stdlib::middlewares::request_id::load_request_id_middleware;
+use crate::stdlib::middlewares::session::load_session_middleware;
+use crate::stdlib::middlewares::etag::load_etag_middleware;
+use crate::stdlib::middlewares::idempotency::load_idempotency_middleware;
 use crate::stdlib::pipeline_items::logical::load_pipeline_logical_items;
 use crate::stdlib::pipeline_items::math::load_pipeline_math_items;
 use crate::stdlib::pipeline_items::model_object::load_pipeline_model_object_items;
@@ -24,6 +29,10 @@ use crate::stdlib::pipeline_items::datetime::load_pipeline_datetime_items;
 use crate::stdlib::pipeline_items::debug::load_debug_items;
 use crate::stdlib::structs::load_structs;
 use crate::stdlib::identity::load_identity_library;
+use crate::stdlib::subscription::load_subscription_library;
+use crate::stdlib::openapi::load_openapi_library;
+use crate::stdlib::graphql::load_graphql_library;
+use crate::stdlib::batch::load_batch_library;
 use crate::stdlib::pipeline_items::request::load_pipeline_request_items;
 
 pub fn load(namespace: &mut Namespace) {
@@ -42,6 +51,7 @@ pub fn load(namespace: &mut Namespace) {
     load_enum_member_decorators(std_namespace);
     load_interface_decorators(std_namespace);
     load_handler_decorators(std_namespace);
+    load_handler_group_decorators(std_namespace);
     // pipeline items
     load_pipeline_math_items(std_namespace);
     load_pipeline_number_items(std_namespace);
@@ -59,7 +69,15 @@ pub fn load(namespace: &mut Namespace) {
     load_bcrypt_items(std_namespace);
     // middlewares
     load_log_request_middleware(std_namespace);
+    load_request_id_middleware(std_namespace);
+    load_session_middleware(std_namespace);
+    load_etag_middleware(std_namespace);
+    load_idempotency_middleware(std_namespace);
     // libraries
     load_identity_library(std_namespace);
     load_admin_library(std_namespace);
+    load_subscription_library(std_namespace);
+    load_openapi_library(std_namespace);
+    load_graphql_library(std_namespace);
+    load_batch_library(std_namespace);
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use crate::arguments::Arguments;
+use crate::coder::binary::BinaryFormat;
+use crate::middleware::compression::negotiate_encoding;
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::namespace::Namespace;
+use crate::request::ctx::Ctx;
+use crate::response::body::Body;
+use crate::response::Response;
+use crate::utils::parse_http_date;
+
+pub(in crate::stdlib) fn load_etag_middleware(namespace: &mut Namespace) {
+    namespace.define_middleware("etag", |arguments: Arguments| async move {
+        let weak: bool = arguments.get_optional("weak")?.unwrap_or(false);
+        Ok(Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+            let method = ctx.request().method().to_uppercase();
+            let representation = representation(&ctx);
+            let res = next.call(ctx.clone()).await?;
+            if (method != "GET" && method != "HEAD") || res.code() != 200 {
+                return Ok(res);
+            }
+            let etag = match res.body().as_teon() {
+                Some(value) => {
+                    let json: JsonValue = value.try_into()?;
+                    let etag = teon_etag(&serde_json::to_vec(&json).unwrap(), &representation, weak);
+                    res.headers().set("etag", etag.clone());
+                    res.headers().append("vary", "accept");
+                    res.headers().append("vary", "accept-encoding");
+                    Some(etag)
+                }
+                None => res.headers().get("etag"),
+            };
+            let not_modified = if let Some(if_none_match) = ctx.request().headers().get("if-none-match") {
+                etag.as_ref().is_some_and(|etag| etag_matches(if_none_match, etag))
+            } else if let Some(if_modified_since) = ctx.request().headers().get("if-modified-since") {
+                match (parse_http_date(if_modified_since), res.headers().get("last-modified").and_then(|l| parse_http_date(&l))) {
+                    (Some(since), Some(last_modified)) => last_modified <= since,
+                    _ => false,
+                }
+            } else {
+                false
+            };
+            if !not_modified {
+                return Ok(res);
+            }
+            into_not_modified(&res);
+            Ok(res)
+        })) as &dyn Middleware)
+    });
+}
+
+/// Turn `res` into a 304. The cookies and the validator and caching headers
+/// are kept, the body and its headers are dropped.
+fn into_not_modified(res: &Response) {
+    res.set_code(304);
+    res.set_body(Body::empty());
+    for key in ["content-type", "content-length", "content-encoding", "content-disposition"] {
+        res.headers().remove(key);
+    }
+}
+
+/// The content type and content coding the response encoding middlewares
+/// will pick for this request. A strong tag must differ between them.
+fn representation(ctx: &Ctx) -> String {
+    let server = ctx.namespace().server.as_ref();
+    let content_type = ctx.request().headers().get("accept")
+        .filter(|_| server.is_some_and(|server| server.binary))
+        .and_then(BinaryFormat::from_accept)
+        .map_or("application/json", |format| format.content_type());
+    let encoding = server
+        .and_then(|server| server.compression.as_ref())
+        .zip(ctx.request().headers().get("accept-encoding"))
+        .and_then(|(compression, accept_encoding)| negotiate_encoding(accept_encoding, &compression.encodings))
+        .map_or("identity", |encoding| encoding.name());
+    format!("{};{}", content_type, encoding)
+}
+
+fn teon_etag(payload: &[u8], representation: &str, weak: bool) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(payload);
+    if !weak {
+        hasher.update(b"\n");
+        hasher.update(representation.as_bytes());
+    }
+    let tag = format!("\"{}\"", URL_SAFE_NO_PAD.encode(hasher.finalize()));
+    if weak { format!("W/{}", tag) } else { tag }
+}
+
+/// `If-None-Match` uses the weak comparison.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let etag = etag.trim_start_matches("W/");
+    if_none_match.split(',').map(|t| t.trim()).any(|t| t == "*" || t.trim_start_matches("W/") == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::response::Cookie;
+    use super::*;
+
+    #[test]
+    fn not_modified_keeps_cookies_and_validators() {
+        let res = Response::string("body", "text/plain");
+        res.headers().set("etag", "\"a\"");
+        res.headers().set("cache-control", "no-cache");
+        res.headers().set("content-type", "text/plain");
+        res.add_cookie(Cookie::new("session", "1")).unwrap();
+        into_not_modified(&res);
+        assert_eq!(res.code(), 304);
+        assert!(res.body().is_empty());
+        assert_eq!(res.headers().get("etag").as_deref(), Some("\"a\""));
+        assert_eq!(res.headers().get("cache-control").as_deref(), Some("no-cache"));
+        assert!(res.headers().get("content-type").is_none());
+        assert_eq!(res.set_cookie_headers(), vec!["session=1"]);
+    }
+
+    #[test]
+    fn strong_tags_differ_between_representations() {
+        let payload = br#"{"id":1}"#;
+        let json = teon_etag(payload, "application/json;identity", false);
+        assert_eq!(json, teon_etag(payload, "application/json;identity", false));
+        assert_ne!(json, teon_etag(payload, "application/json;gzip", false));
+        assert_ne!(json, teon_etag(payload, "application/msgpack;identity", false));
+        assert_ne!(json, teon_etag(br#"{"id":2}"#, "application/json;identity", false));
+    }
+
+    #[test]
+    fn weak_tags_are_shared_between_representations() {
+        let payload = br#"{"id":1}"#;
+        let tag = teon_etag(payload, "application/json;identity", true);
+        assert!(tag.starts_with("W/\""));
+        assert_eq!(tag, teon_etag(payload, "application/cbor;br", true));
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        assert!(etag_matches("\"a\"", "\"a\""));
+        assert!(etag_matches("W/\"a\"", "\"a\""));
+        assert!(etag_matches("\"b\", W/\"a\"", "W/\"a\""));
+        assert!(etag_matches("*", "\"a\""));
+        assert!(!etag_matches("\"b\"", "\"a\""));
+    }
+}
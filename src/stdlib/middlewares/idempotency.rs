@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use std::time::Duration;
+use teo_result::Error;
+use crate::arguments::Arguments;
+use crate::idempotency::{idempotency_middleware, IdempotencyOptions, MemoryStore};
+use crate::namespace::Namespace;
+
+pub(in crate::stdlib) fn load_idempotency_middleware(namespace: &mut Namespace) {
+    namespace.define_middleware("idempotency", |arguments: Arguments| async move {
+        let header_name: Option<String> = arguments.get_optional("headerName")?;
+        let ttl: Option<i64> = arguments.get_optional("ttl")?;
+        let mut options = IdempotencyOptions::default();
+        if let Some(header_name) = header_name {
+            options.header_name = header_name.to_lowercase();
+        }
+        if let Some(ttl) = ttl {
+            if ttl <= 0 {
+                return Err(Error::new("idempotency ttl must be positive"));
+            }
+            options.ttl = Duration::from_secs(ttl as u64);
+        }
+        idempotency_middleware(Arc::new(MemoryStore::new()), options)
+    });
+}
@@ -1,18 +1,163 @@
+use std::time::Instant;
+use log::Level;
+use serde_json::{json, Value as JsonValue};
+use teo_result::Error;
 use crate::arguments::Arguments;
 use crate::middleware::middleware::Middleware;
 use crate::middleware::next::Next;
 use crate::namespace::Namespace;
 use crate::request::ctx::Ctx;
+use crate::response::body::BodyInner;
+use crate::response::Response;
+use crate::value::interface_enum_variant::InterfaceEnumVariant;
+use crate::value::Value;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum LogRequestFormat {
+    Text,
+    Json,
+}
+
+impl TryFrom<&Value> for LogRequestFormat {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let enum_variant: InterfaceEnumVariant = value.try_into()?;
+        match enum_variant.value.as_str() {
+            "text" => Ok(LogRequestFormat::Text),
+            "json" => Ok(LogRequestFormat::Json),
+            _ => Err(Error::new(format!("invalid log request format: {:?}", value)))
+        }
+    }
+}
+
+struct LogRequestLevel(Level);
+
+impl TryFrom<&Value> for LogRequestLevel {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let enum_variant: InterfaceEnumVariant = value.try_into()?;
+        match enum_variant.value.as_str() {
+            "error" => Ok(LogRequestLevel(Level::Error)),
+            "warn" => Ok(LogRequestLevel(Level::Warn)),
+            "info" => Ok(LogRequestLevel(Level::Info)),
+            "debug" => Ok(LogRequestLevel(Level::Debug)),
+            "trace" => Ok(LogRequestLevel(Level::Trace)),
+            _ => Err(Error::new(format!("invalid log request level: {:?}", value)))
+        }
+    }
+}
 
 pub(in crate::stdlib) fn load_log_request_middleware(namespace: &mut Namespace) {
     namespace.define_middleware("logRequest", |arguments: Arguments| async move {
-        let timing: bool = arguments.get("timing")?;
+        let timing: bool = arguments.get_optional("timing")?.unwrap_or(false);
+        let format: LogRequestFormat = arguments.get_optional("format")?.unwrap_or(LogRequestFormat::Text);
+        let level: Option<LogRequestLevel> = arguments.get_optional("level")?;
+        let level = level.map_or(Level::Info, |l| l.0);
         Ok(Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
-            if timing {
-                let start = 1;
+            let start = Instant::now();
+            let method = ctx.request().method().to_owned();
+            let path = ctx.request().path().to_owned();
+            let mut handler_path = ctx.handler_match().path();
+            handler_path.push(ctx.handler_match().handler_name());
+            let handler = handler_path.join(".");
+            let res = next.call(ctx.clone()).await;
+            let elapsed = if timing { Some(start.elapsed().as_secs_f64() * 1000.0) } else { None };
+            let account = account_identifier(&ctx);
+            let request_id = ctx.request_id();
+            match &res {
+                Ok(response) => {
+                    let code = response.code();
+                    let size = response_size(response);
+                    log::log!(target: "teo::request", level, "{}", match format {
+                        LogRequestFormat::Text => format_text(&method, &path, &handler, code, elapsed, Some(size), account.as_ref(), request_id.as_deref(), None),
+                        LogRequestFormat::Json => format_json(&method, &path, &handler, code, elapsed, Some(size), account.as_ref(), request_id.as_deref(), None),
+                    });
+                }
+                Err(error) => {
+                    let error_level = if error.code >= 500 { Level::Error } else { level };
+                    log::log!(target: "teo::request", error_level, "{}", match format {
+                        LogRequestFormat::Text => format_text(&method, &path, &handler, error.code, elapsed, None, account.as_ref(), request_id.as_deref(), Some(error)),
+                        LogRequestFormat::Json => format_json(&method, &path, &handler, error.code, elapsed, None, account.as_ref(), request_id.as_deref(), Some(error)),
+                    });
+                }
             }
-            let res = next.call(ctx).await;
-            return res;
+            res
         })) as &dyn Middleware)
     });
-}
\ No newline at end of file
+}
+
+fn account_identifier(ctx: &Ctx) -> Option<JsonValue> {
+    let binding = ctx.data();
+    let account: &Value = binding.get("account")?;
+    let object = account.as_model_object()?;
+    object.identifier().try_into().ok()
+}
+
+fn response_size(response: &Response) -> u64 {
+    match response.body().inner.as_ref() {
+        BodyInner::Empty => 0,
+        BodyInner::String(s) => s.len() as u64,
+        BodyInner::File(path) => std::fs::metadata(path).map_or(0, |m| m.len()),
+        BodyInner::Teon(value) => serde_json::to_vec(value).map_or(0, |v| v.len() as u64),
+        BodyInner::Stream(stream) => stream.length().unwrap_or(0),
+        BodyInner::Sse(_) => 0,
+    }
+}
+
+fn format_text(method: &str, path: &str, handler: &str, code: u16, elapsed: Option<f64>, size: Option<u64>, account: Option<&JsonValue>, request_id: Option<&str>, error: Option<&Error>) -> String {
+    let mut result = format!("{} {} {} {}", method, path, handler, code);
+    if let Some(elapsed) = elapsed {
+        result += &format!(" {:.3}ms", elapsed);
+    }
+    if let Some(size) = size {
+        result += &format!(" {}B", size);
+    }
+    if let Some(account) = account {
+        result += &format!(" account={}", account);
+    }
+    if let Some(request_id) = request_id {
+        result += &format!(" requestId={}", request_id);
+    }
+    if let Some(error) = error {
+        result += &format!(" error=\"{}\"", error.message.replace("\"", "\\\""));
+        if let Some(errors) = &error.errors {
+            for (key, message) in errors {
+                result += &format!(" {}=\"{}\"", key, message.replace("\"", "\\\""));
+            }
+        }
+    }
+    result
+}
+
+fn format_json(method: &str, path: &str, handler: &str, code: u16, elapsed: Option<f64>, size: Option<u64>, account: Option<&JsonValue>, request_id: Option<&str>, error: Option<&Error>) -> String {
+    let mut result = json!({
+        "method": method,
+        "path": path,
+        "handler": handler,
+        "code": code,
+    });
+    let object = result.as_object_mut().unwrap();
+    if let Some(elapsed) = elapsed {
+        object.insert("durationMs".to_owned(), json!(elapsed));
+    }
+    if let Some(size) = size {
+        object.insert("size".to_owned(), json!(size));
+    }
+    if let Some(account) = account {
+        object.insert("account".to_owned(), account.clone());
+    }
+    if let Some(request_id) = request_id {
+        object.insert("requestId".to_owned(), json!(request_id));
+    }
+    if let Some(error) = error {
+        object.insert("error".to_owned(), json!({
+            "message": error.message,
+            "errors": error.errors.as_ref().map(|errors| errors.iter().map(|(k, v)| (k.to_string(), JsonValue::String(v.to_string()))).collect::<serde_json::Map<String, JsonValue>>()),
+        }));
+    }
+    result.to_string()
+}
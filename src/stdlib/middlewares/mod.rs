@@ -1 +1,5 @@
 pub(super) mod log_request;
+pub(super) mod request_id;
+pub(super) mod session;
+pub(super) mod etag;
+pub(super) mod idempotency;
@@ -0,0 +1,27 @@
+use uuid::Uuid;
+use crate::arguments::Arguments;
+use crate::middleware::middleware::Middleware;
+use crate::middleware::next::Next;
+use crate::namespace::Namespace;
+use crate::request::ctx::Ctx;
+
+pub(in crate::stdlib) fn load_request_id_middleware(namespace: &mut Namespace) {
+    namespace.define_middleware("requestId", |arguments: Arguments| async move {
+        let header_name: String = arguments.get_optional("header")?.unwrap_or("x-request-id".to_owned());
+        let header_name = Box::leak(Box::new(header_name.to_lowercase())).as_str();
+        Ok(Box::leak(Box::new(move |ctx: Ctx, next: &'static dyn Next| async move {
+            let request_id = match ctx.request().headers().get(header_name) {
+                Some(value) if is_valid_request_id(value) => value.to_owned(),
+                _ => Uuid::new_v4().to_string(),
+            };
+            ctx.set_request_id(request_id.clone());
+            let res = next.call(ctx).await?;
+            res.headers().set(header_name, request_id);
+            Ok(res)
+        })) as &dyn Middleware)
+    });
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty() && value.len() <= 200 && value.chars().all(|c| c.is_ascii_graphic())
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::time::Duration;
+use teo_result::Error;
+use crate::arguments::Arguments;
+use crate::namespace::Namespace;
+use crate::response::cookie::readwrite::SameSite;
+use crate::session::{MemoryStore, ModelStore, session_middleware, SessionOptions, SessionStore};
+use crate::value::interface_enum_variant::InterfaceEnumVariant;
+use crate::value::Value;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SessionStoreKind {
+    Memory,
+    Model,
+}
+
+impl TryFrom<&Value> for SessionStoreKind {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let enum_variant: InterfaceEnumVariant = value.try_into()?;
+        match enum_variant.value.as_str() {
+            "memory" => Ok(SessionStoreKind::Memory),
+            "model" => Ok(SessionStoreKind::Model),
+            _ => Err(Error::new(format!("invalid session store: {:?}", value)))
+        }
+    }
+}
+
+struct SessionSameSite(SameSite);
+
+impl TryFrom<&Value> for SessionSameSite {
+
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let enum_variant: InterfaceEnumVariant = value.try_into()?;
+        match enum_variant.value.as_str() {
+            "strict" => Ok(SessionSameSite(SameSite::Strict)),
+            "lax" => Ok(SessionSameSite(SameSite::Lax)),
+            "none" => Ok(SessionSameSite(SameSite::None)),
+            _ => Err(Error::new(format!("invalid session same site: {:?}", value)))
+        }
+    }
+}
+
+pub(in crate::stdlib) fn load_session_middleware(namespace: &mut Namespace) {
+    namespace.define_middleware("session", |arguments: Arguments| async move {
+        let store_kind: SessionStoreKind = arguments.get_optional("store")?.unwrap_or(SessionStoreKind::Memory);
+        let store: Arc<dyn SessionStore> = match store_kind {
+            SessionStoreKind::Memory => Arc::new(MemoryStore::new()),
+            SessionStoreKind::Model => {
+                let model: Option<String> = arguments.get_optional("model")?;
+                let Some(model) = model else {
+                    return Err(Error::new("session model store requires `model`"));
+                };
+                Arc::new(ModelStore::new(model.split(".").map(|s| s.to_owned()).collect()))
+            }
+        };
+        let cookie_name: Option<String> = arguments.get_optional("cookieName")?;
+        let ttl: Option<i64> = arguments.get_optional("ttl")?;
+        let secure: Option<bool> = arguments.get_optional("secure")?;
+        let mut options = SessionOptions::default();
+        if let Some(cookie_name) = cookie_name {
+            options.cookie_name = cookie_name;
+        }
+        if let Some(ttl) = ttl {
+            if ttl <= 0 {
+                return Err(Error::new("session ttl must be positive"));
+            }
+            options.ttl = Duration::from_secs(ttl as u64);
+        }
+        if let Some(secure) = secure {
+            options.secure = secure;
+        }
+        let same_site: Option<SessionSameSite> = arguments.get_optional("sameSite")?;
+        if let Some(same_site) = same_site {
+            options.same_site = same_site.0;
+        }
+        session_middleware(store, options)
+    });
+}
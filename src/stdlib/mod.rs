@@ -4,4 +4,8 @@ mod decorators;
 mod middlewares;
 mod structs;
 mod identity;
-mod admin;
\ No newline at end of file
+mod admin;
+mod subscription;
+mod openapi;
+mod graphql;
+mod batch;
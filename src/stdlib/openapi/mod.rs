@@ -0,0 +1,14 @@
+use crate::namespace::Namespace;
+use crate::openapi::{generate_openapi, OpenApiOptions};
+use crate::request;
+use crate::response::Response;
+
+pub(super) fn load_openapi_library(std_namespace: &mut Namespace) {
+
+    let openapi_namespace = std_namespace.namespace_mut_or_create("openapi");
+
+    openapi_namespace.define_handler_template("document", |req_ctx: request::Ctx| async move {
+        let document = generate_openapi(req_ctx.namespace(), &OpenApiOptions::default());
+        Ok(Response::string(document.to_string(), "application/json"))
+    });
+}
@@ -15,4 +15,11 @@ pub(in crate::stdlib) fn load_pipeline_request_items(namespace: &mut Namespace)
         };
         Ok(object.clone())
     });
+
+    namespace.define_pipeline_item("requestId", |_args: Arguments, ctx: Ctx| async move {
+        let Some(request_ctx) = ctx.request_ctx() else {
+            return Ok(Value::Null);
+        };
+        Ok(Value::from(request_ctx.request_id()))
+    });
 }
\ No newline at end of file
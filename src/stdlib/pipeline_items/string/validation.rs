@@ -129,7 +129,7 @@ pub(in crate::stdlib) fn load_pipeline_string_validation_items(namespace: &mut N
         ).await?;
         let arg: &str = arg_object.try_ref_into_err_prefix("hasPrefix")?;
         if !input.starts_with(arg) {
-            Err(Error::new_with_code(format!("input doesn't have suffix \"{arg}\""), 400))?
+            Err(Error::new_with_code(format!("input doesn't have prefix \"{arg}\""), 400))?
         }
         Ok(ctx.value().clone())
     });
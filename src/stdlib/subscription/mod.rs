@@ -0,0 +1,27 @@
+use serde_json::{json, Value as JsonValue};
+use teo_result::Error;
+use crate::action::action::FIND_MANY_HANDLER;
+use crate::handler::input::validate_and_transform_json_input_for_builtin_action;
+use crate::model::subscription::subscribe;
+use crate::namespace::Namespace;
+use crate::request;
+use crate::response::Response;
+use crate::value::Value;
+
+pub(super) fn load_subscription_library(std_namespace: &mut Namespace) {
+
+    let subscription_namespace = std_namespace.namespace_mut_or_create("subscription");
+
+    subscription_namespace.define_handler_template("subscribe", |req_ctx: request::Ctx| async move {
+        let Some(model) = req_ctx.namespace().model_at_path(&req_ctx.handler_match().path()) else {
+            return Err(Error::internal_server_error_message("subscribe handler should be declared in a model's handler group"));
+        };
+        let body: JsonValue = match req_ctx.body() {
+            Value::Null => json!({}),
+            body => body.try_into()?,
+        };
+        let finder = validate_and_transform_json_input_for_builtin_action(model, FIND_MANY_HANDLER, &body, req_ctx.namespace())?;
+        let events = subscribe(model, finder, req_ctx).await?;
+        Ok(Response::sse(events))
+    });
+}
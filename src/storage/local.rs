@@ -0,0 +1,130 @@
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use teo_result::{Error, Result};
+use uuid::Uuid;
+use crate::storage::storage::FileStorage;
+use crate::value::file::File;
+
+/// Stores files in a directory on the local file system.
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    pub root: PathBuf,
+    pub base_url: String,
+}
+
+impl LocalStorage {
+
+    pub fn new(root: impl Into<PathBuf>, base_url: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            base_url: base_url.into(),
+        }
+    }
+
+    fn path_for_key(&self, key: &str) -> Result<PathBuf> {
+        if key.is_empty() || key.contains('/') || key.contains('\\') || key.starts_with('.') {
+            return Err(Error::new(format!("invalid storage key: {}", key)));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl FileStorage for LocalStorage {
+
+    async fn put(&self, file: &File) -> Result<String> {
+        let key = match &file.filename_ext {
+            Some(ext) => format!("{}.{}", Uuid::new_v4(), ext),
+            None => Uuid::new_v4().to_string(),
+        };
+        let destination = self.path_for_key(&key)?;
+        tokio::fs::create_dir_all(&self.root).await.map_err(|e| Error::new(e.to_string()))?;
+        let source = Path::new(&file.filepath);
+        if tokio::fs::rename(source, &destination).await.is_err() {
+            // the temp directory may live on another device
+            tokio::fs::copy(source, &destination).await.map_err(|e| Error::new(e.to_string()))?;
+            let _ = tokio::fs::remove_file(source).await;
+        }
+        Ok(key)
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        tokio::fs::read(self.path_for_key(key)?).await.map_err(|e| Error::new(e.to_string()))
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path_for_key(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(Error::new(e.to_string())),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), key)
+    }
+
+    fn key_for_url(&self, url: &str) -> Option<String> {
+        url.strip_prefix(self.base_url.trim_end_matches('/'))?.strip_prefix('/').map(|k| k.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use uuid::Uuid;
+    use crate::storage::storage::FileStorage;
+    use crate::value::file::File;
+    use super::LocalStorage;
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("teo-storage-{}", Uuid::new_v4()))
+    }
+
+    async fn upload(content: &str) -> File {
+        let path = std::env::temp_dir().join(format!("teo-upload-{}.txt", Uuid::new_v4()));
+        tokio::fs::write(&path, content).await.unwrap();
+        File {
+            filepath: path.to_str().unwrap().to_owned(),
+            content_type: Some("text/plain".to_owned()),
+            filename: "upload.txt".to_owned(),
+            filename_ext: Some("txt".to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn put_moves_the_upload_under_a_new_key() {
+        let storage = LocalStorage::new(temp_dir(), "/files");
+        let file = upload("hello").await;
+        let key = storage.put(&file).await.unwrap();
+        assert!(key.ends_with(".txt"));
+        assert_eq!(storage.get(&key).await.unwrap(), b"hello");
+        assert!(!std::path::Path::new(&file.filepath).exists());
+    }
+
+    #[tokio::test]
+    async fn delete_removes_the_blob_and_ignores_missing_ones() {
+        let storage = LocalStorage::new(temp_dir(), "/files");
+        let key = storage.put(&upload("hello").await).await.unwrap();
+        storage.delete(&key).await.unwrap();
+        assert!(storage.get(&key).await.is_err());
+        storage.delete(&key).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn keys_cannot_escape_the_root() {
+        let storage = LocalStorage::new(temp_dir(), "/files");
+        assert!(storage.get("../secret").await.is_err());
+        assert!(storage.delete("a/b").await.is_err());
+        assert!(storage.get(".hidden").await.is_err());
+    }
+
+    #[test]
+    fn urls_map_back_to_keys() {
+        let storage = LocalStorage::new(temp_dir(), "https://cdn.example.com/files/");
+        let url = storage.url("abc.png");
+        assert_eq!(url, "https://cdn.example.com/files/abc.png");
+        assert_eq!(storage.key_for_url(&url), Some("abc.png".to_owned()));
+        assert_eq!(storage.key_for_url("https://other.example.com/abc.png"), None);
+    }
+}
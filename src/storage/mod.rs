@@ -0,0 +1,5 @@
+pub mod storage;
+pub mod local;
+
+pub use storage::FileStorage;
+pub use local::LocalStorage;
@@ -0,0 +1,21 @@
+use std::fmt::Debug;
+use async_trait::async_trait;
+use teo_result::Result;
+use crate::value::file::File;
+
+#[async_trait]
+pub trait FileStorage: Send + Sync + Debug {
+
+    /// Persist an uploaded file and return a stable key for it.
+    async fn put(&self, file: &File) -> Result<String>;
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// The public url of the file stored under `key`.
+    fn url(&self, key: &str) -> String;
+
+    /// Recover the key from a url returned by `url`.
+    fn key_for_url(&self, url: &str) -> Option<String>;
+}
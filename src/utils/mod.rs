@@ -1,5 +1,7 @@
 pub mod find_main_schema_file;
 
+use chrono::{DateTime, Utc};
+
 pub use find_main_schema_file::find_main_schema_file;
 
 pub(crate) fn next_path(path: &Vec<String>, name: &str) -> Vec<String> {
@@ -8,6 +10,52 @@ pub(crate) fn next_path(path: &Vec<String>, name: &str) -> Vec<String> {
     new_path
 }
 
+/// Decode `%XX` escapes, and `+` as space when `plus_as_space` is set.
+/// Invalid escapes are kept as they are.
+pub(crate) fn percent_decode(value: &str, plus_as_space: bool) -> String {
+    let bytes = value.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = std::str::from_utf8(&bytes[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                result.push(byte);
+                i += 3;
+                continue
+            }
+        }
+        if bytes[i] == b'+' && plus_as_space {
+            result.push(b' ');
+        } else {
+            result.push(bytes[i]);
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).to_string()
+}
+
+/// Format as an HTTP date, e.g. `Wed, 21 Oct 2015 07:28:00 GMT`.
+pub(crate) fn http_date(datetime: &DateTime<Utc>) -> String {
+    datetime.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+pub(crate) fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value).ok().map(|d| d.with_timezone(&Utc))
+}
+
+/// `ttl` as a chrono duration. `None` when a time `ttl` from now can't be
+/// represented.
+pub(crate) fn expiry_ttl(ttl: std::time::Duration) -> Option<chrono::Duration> {
+    let ttl = chrono::Duration::from_std(ttl).ok()?;
+    Utc::now().checked_add_signed(ttl)?;
+    Some(ttl)
+}
+
+/// The time `ttl` from now.
+pub(crate) fn expires_at(ttl: chrono::Duration) -> teo_result::Result<DateTime<Utc>> {
+    Utc::now().checked_add_signed(ttl).ok_or_else(|| teo_result::Error::internal_server_error_message("expiry time is out of range"))
+}
+
 pub trait ContainsStr {
 
     fn contains_str(&self, str: &str) -> bool;
@@ -18,4 +66,17 @@ impl ContainsStr for Vec<String> {
     fn contains_str(&self, str: &str) -> bool {
         self.iter().find(|v| v.as_str() == str).is_some()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::expiry_ttl;
+
+    #[test]
+    fn expiry_ttl_rejects_unrepresentable_expiry() {
+        assert_eq!(expiry_ttl(Duration::from_secs(60)), Some(chrono::Duration::seconds(60)));
+        assert_eq!(expiry_ttl(Duration::MAX), None);
+        assert_eq!(expiry_ttl(Duration::from_secs(60 * 60 * 24 * 365 * 1_000_000)), None);
+    }
+}
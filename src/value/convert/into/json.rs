@@ -20,6 +20,7 @@ impl TryFrom<&Value> for JsonValue {
             Value::String(val) => JsonValue::String(val.clone()),
             Value::Date(val) => json!({"$date": val.format("%Y-%m-%d").to_string()}),
             Value::DateTime(val) => json!({"$datetime": val.to_rfc3339_opts(SecondsFormat::Millis, true)}),
+            Value::File(val) => json!(val),
             Value::Array(val) => {
                 let mut vec = vec![];
                 for v in val {